@@ -0,0 +1,276 @@
+use core::{f64::consts::PI, time::Duration};
+use num_traits::real::Real;
+
+use crate::controller::FeedbackController;
+
+/// A set of rules for computing PID gains from a system's ultimate gain and period.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub enum TuningRule {
+    /// The classic Ziegler–Nichols rule. Responds quickly, but with noticeable overshoot.
+    #[default]
+    ZieglerNichols,
+
+    /// The Tyreus–Luyben rule. More conservative than Ziegler–Nichols, with less overshoot and
+    /// better robustness at the cost of a slower response.
+    TyreusLuyben,
+
+    /// A variant of Ziegler–Nichols with a much lower proportional gain intended to prevent overshoot.
+    NoOvershoot,
+}
+
+/// The measurements produced by a [`RelayAutotuner`].
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct RelayTuningResult {
+    /// The proportional gain at which the system oscillates with a constant amplitude.
+    pub ultimate_gain: f64,
+
+    /// The period of the system's oscillations at the ultimate gain in seconds.
+    pub ultimate_period: f64,
+}
+
+impl RelayTuningResult {
+    /// Compute suggested PID gains as a tuple (`kp`, `ki`, `kd`) using a tuning rule.
+    ///
+    /// The returned gains can be given directly to [`PIDController::new`](crate::controller::PIDController::new).
+    pub fn gains(&self, rule: TuningRule) -> (f64, f64, f64) {
+        let (ku, tu) = (self.ultimate_gain, self.ultimate_period);
+
+        // Proportional gain, integral time, and derivative time.
+        let (kp, ti, td) = match rule {
+            TuningRule::ZieglerNichols => (0.6 * ku, tu / 2.0, tu / 8.0),
+            TuningRule::TyreusLuyben => (ku / 2.2, 2.2 * tu, tu / 6.3),
+            TuningRule::NoOvershoot => (0.2 * ku, tu / 2.0, tu / 3.0),
+        };
+
+        (kp, kp / ti, kp * td)
+    }
+}
+
+/// An Åström–Hägglund relay feedback auto-tuner.
+///
+/// Rather than controlling a system, this controller switches its output between `+amplitude` and
+/// `-amplitude` depending on the sign of the error, which causes most systems to settle into a steady
+/// oscillation around the setpoint. By measuring the size and period of these oscillations, the system's
+/// ultimate gain and period can be estimated and used to compute PID gains (see [`RelayTuningResult::gains`]).
+///
+/// Since [`RelayAutotuner`] implements [`FeedbackController`], it can be used in place of a drivetrain's
+/// drive or turn controller. After giving the drivetrain a target, the tuner will output zero once it has
+/// measured enough oscillations, and [`RelayAutotuner::result`] will return its measurements.
+///
+/// # Example
+///
+/// ```
+/// let mut drivetrain = DifferentialDrivetrain::new(
+///     (left_motors, right_motors),
+///     tracking,
+///     PIDController::new((3.0, 0.0, 0.0), 0.3),
+///     RelayAutotuner::new(40.0, 0.02, 4),
+///     // ...
+/// );
+///
+/// drivetrain.turn_to_angle(90.0.to_radians(), None);
+///
+/// let turn_controller = drivetrain.turn_controller();
+/// while !turn_controller.lock().is_finished() {}
+///
+/// let gains = turn_controller.lock().result().unwrap().gains(TuningRule::TyreusLuyben);
+/// ```
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct RelayAutotuner {
+    /// The magnitude of the relay's output.
+    pub amplitude: f64,
+
+    /// The error magnitude that must be crossed before the relay switches.
+    ///
+    /// This prevents noise around the setpoint from rapidly switching the relay.
+    pub hysteresis: f64,
+
+    /// The number of oscillations to measure before producing a result.
+    ///
+    /// At least one oscillation is always measured, so a value of `0` behaves the same as `1`.
+    pub cycles: usize,
+
+    output: f64,
+    elapsed: f64,
+    last_switch_time: Option<f64>,
+    max_error: f64,
+    min_error: f64,
+    measured_cycles: usize,
+    period_sum: f64,
+    peak_sum: f64,
+    result: Option<RelayTuningResult>,
+}
+
+impl RelayAutotuner {
+    /// Construct a new [`RelayAutotuner`] from a relay amplitude, hysteresis band, and number of
+    /// oscillations to measure.
+    pub fn new(amplitude: f64, hysteresis: f64, cycles: usize) -> Self {
+        Self {
+            amplitude,
+            hysteresis,
+            cycles,
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if enough oscillations have been measured to produce a result.
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Get the measured ultimate gain and period, if tuning has finished.
+    pub fn result(&self) -> Option<RelayTuningResult> {
+        self.result
+    }
+
+    /// Discards all measurements and restarts tuning.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.amplitude, self.hysteresis, self.cycles);
+    }
+
+    /// Records a completed oscillation ending at the current time.
+    fn record_cycle(&mut self) {
+        if let Some(last_switch_time) = self.last_switch_time {
+            // The first oscillation is discarded, since the system is still moving towards the setpoint.
+            if self.measured_cycles > 0 {
+                self.period_sum += self.elapsed - last_switch_time;
+                self.peak_sum += (self.max_error - self.min_error) / 2.0;
+            }
+
+            self.measured_cycles += 1;
+        }
+
+        self.last_switch_time = Some(self.elapsed);
+        self.max_error = 0.0;
+        self.min_error = 0.0;
+
+        let cycles = self.cycles.max(1);
+
+        if self.measured_cycles > cycles {
+            let count = cycles as f64;
+            let (period, peak) = (self.period_sum / count, self.peak_sum / count);
+
+            // Describing function of a relay with hysteresis.
+            let ultimate_gain = 4.0 * self.amplitude
+                / (PI * (peak.powi(2) - self.hysteresis.powi(2)).max(0.0).sqrt().max(f64::EPSILON));
+
+            self.result = Some(RelayTuningResult {
+                ultimate_gain,
+                ultimate_period: period,
+            });
+        }
+    }
+}
+
+impl FeedbackController for RelayAutotuner {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        if self.is_finished() {
+            return 0.0;
+        }
+
+        self.elapsed += dt.as_secs_f64();
+        self.max_error = self.max_error.max(error);
+        self.min_error = self.min_error.min(error);
+
+        if error > self.hysteresis && self.output <= 0.0 {
+            // A full oscillation is measured between each switch to a positive output.
+            self.record_cycle();
+            self.output = self.amplitude;
+        } else if error < -self.hysteresis && self.output >= 0.0 {
+            self.output = -self.amplitude;
+        }
+
+        if self.is_finished() {
+            0.0
+        } else {
+            self.output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+
+    const DT: Duration = Duration::from_millis(10);
+
+    /// Runs a tuner against a delayed second-order plant until it finishes, returning the
+    /// number of updates it took.
+    fn tune(tuner: &mut RelayAutotuner) -> usize {
+        let (mut position, mut velocity) = (0.0, 0.0);
+        let mut delay = VecDeque::from([0.0; 5]);
+
+        for step in 0..100_000 {
+            let output = tuner.update(1.0 - position, DT);
+
+            delay.push_back(output);
+            let delayed_output = delay.pop_front().unwrap();
+
+            velocity += (delayed_output - velocity) * DT.as_secs_f64();
+            position += velocity * DT.as_secs_f64();
+
+            if tuner.is_finished() {
+                return step;
+            }
+        }
+
+        panic!("tuner did not finish");
+    }
+
+    #[test]
+    fn relay_switches_with_error_sign() {
+        let mut tuner = RelayAutotuner::new(2.0, 0.1, 4);
+
+        assert_eq!(tuner.update(0.05, DT), 0.0);
+        assert_eq!(tuner.update(1.0, DT), 2.0);
+        assert_eq!(tuner.update(0.0, DT), 2.0);
+        assert_eq!(tuner.update(-1.0, DT), -2.0);
+        assert_eq!(tuner.result(), None);
+    }
+
+    #[test]
+    fn relay_measures_oscillation() {
+        let mut tuner = RelayAutotuner::new(1.0, 0.01, 4);
+        tune(&mut tuner);
+
+        let result = tuner.result().unwrap();
+        assert!(result.ultimate_gain.is_finite() && result.ultimate_gain > 0.0);
+        assert!(result.ultimate_period.is_finite() && result.ultimate_period > 0.0);
+
+        // Once finished, the tuner stops driving the system.
+        assert_eq!(tuner.update(1.0, DT), 0.0);
+
+        tuner.reset();
+        assert_eq!(tuner.result(), None);
+    }
+
+    #[test]
+    fn zero_cycles_measures_one_cycle() {
+        let mut zero = RelayAutotuner::new(1.0, 0.01, 0);
+        let mut one = RelayAutotuner::new(1.0, 0.01, 1);
+
+        assert_eq!(tune(&mut zero), tune(&mut one));
+        assert_eq!(zero.result(), one.result());
+        assert!(zero.result().unwrap().ultimate_gain.is_finite());
+    }
+
+    #[test]
+    fn tuning_rules_compute_gains() {
+        let result = RelayTuningResult {
+            ultimate_gain: 10.0,
+            ultimate_period: 2.0,
+        };
+
+        let (kp, ki, kd) = result.gains(TuningRule::ZieglerNichols);
+        assert!((kp - 6.0).abs() < 1e-9);
+        assert!((ki - 6.0).abs() < 1e-9);
+        assert!((kd - 1.5).abs() < 1e-9);
+
+        let (kp, ..) = result.gains(TuningRule::NoOvershoot);
+        assert!((kp - 2.0).abs() < 1e-9);
+    }
+}
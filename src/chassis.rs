@@ -0,0 +1,41 @@
+use crate::controller::{Feedforward, SimpleMotorFeedforward};
+
+/// A model of a differential drive chassis used to convert chassis velocities into motor power.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct ChassisModel {
+    /// The distance between the left and right wheels.
+    pub track_width: f64,
+
+    /// The feedforward model converting a side's wheel velocity and acceleration into motor power.
+    pub feedforward: SimpleMotorFeedforward,
+}
+
+impl ChassisModel {
+    /// Construct a new [`ChassisModel`] from a track width and wheel feedforward model.
+    pub fn new(track_width: f64, feedforward: SimpleMotorFeedforward) -> Self {
+        Self {
+            track_width,
+            feedforward,
+        }
+    }
+
+    /// Convert a chassis `linear_velocity` and counterclockwise `angular_velocity` (in radians) into
+    /// the velocities of the left and right wheels.
+    pub fn wheel_velocities(&self, linear_velocity: f64, angular_velocity: f64) -> (f64, f64) {
+        let turn_velocity = angular_velocity * self.track_width / 2.0;
+
+        (linear_velocity - turn_velocity, linear_velocity + turn_velocity)
+    }
+
+    /// Convert a chassis `linear_velocity` and counterclockwise `angular_velocity` (in radians) into
+    /// left and right motor power.
+    pub fn wheel_powers(&self, linear_velocity: f64, angular_velocity: f64) -> (f64, f64) {
+        let (left_velocity, right_velocity) =
+            self.wheel_velocities(linear_velocity, angular_velocity);
+
+        (
+            self.feedforward.calculate(0.0, left_velocity, 0.0),
+            self.feedforward.calculate(0.0, right_velocity, 0.0),
+        )
+    }
+}
@@ -1,6 +1,13 @@
-use core::time::Duration;
+use alloc::vec::Vec;
+use core::{
+    cmp::Ordering,
+    ops::{Add, Neg, Sub},
+    time::Duration,
+};
 use num_traits::real::Real;
 
+use crate::math::wrap;
+
 /// A closed-loop feedback controller.
 ///
 /// At its core, a feedback controller is a simple function that produces an output value
@@ -27,6 +34,71 @@ pub trait FeedbackController: Send + Sync + 'static {
     fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output;
 }
 
+/// A closed-loop feedback controller that is given the setpoint and measurement separately.
+///
+/// This is a more general form of [`FeedbackController`]. Knowing the setpoint and measurement rather than
+/// only their difference allows a controller to do things such as differentiating the measurement
+/// (see [`DerivativeMode::Measurement`]), weighting the setpoint, or computing feedforward from the setpoint.
+///
+/// Any [`FeedbackController`] can be used as a [`SetpointController`] through [`ErrorAdapter`], and any
+/// [`SetpointController`] can be used where a [`FeedbackController`] is expected through [`SetpointAdapter`].
+pub trait SetpointController: Send + Sync + 'static {
+    type Input;
+    type Output;
+
+    /// Produce an output value given a desired state (`setpoint`) and a `measurement` of the system's
+    /// current state.
+    fn update_with_setpoint(
+        &mut self,
+        setpoint: Self::Input,
+        measurement: Self::Input,
+        dt: Duration,
+    ) -> Self::Output;
+}
+
+/// Adapts a [`FeedbackController`] into a [`SetpointController`].
+///
+/// The wrapped controller is given the difference between the setpoint and measurement as its error.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct ErrorAdapter<C: FeedbackController>(pub C);
+
+impl<C: FeedbackController> SetpointController for ErrorAdapter<C>
+where
+    C::Input: Sub<Output = C::Input>,
+{
+    type Input = C::Input;
+    type Output = C::Output;
+
+    fn update_with_setpoint(
+        &mut self,
+        setpoint: Self::Input,
+        measurement: Self::Input,
+        dt: Duration,
+    ) -> Self::Output {
+        self.0.update(setpoint - measurement, dt)
+    }
+}
+
+/// Adapts a [`SetpointController`] into a [`FeedbackController`].
+///
+/// Since only the error is known, the wrapped controller is given a setpoint of zero and the negated
+/// error as its measurement. While the true setpoint is constant, this produces the same output as
+/// if the true setpoint and measurement were known.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct SetpointAdapter<C: SetpointController>(pub C);
+
+impl<C: SetpointController> FeedbackController for SetpointAdapter<C>
+where
+    C::Input: Default + Neg<Output = C::Input>,
+{
+    type Input = C::Input;
+    type Output = C::Output;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        self.0.update_with_setpoint(C::Input::default(), -error, dt)
+    }
+}
+
 /// A proportional-integral-derivative (PID) feedback controller.
 ///
 /// The PID controller is a feedback control algorithm with common applications
@@ -47,15 +119,29 @@ pub trait FeedbackController: Send + Sync + 'static {
 ///
 /// 	> In some scenarios, a PID controller may be prone to *integral windup*, where a controlled system
 /// 	> reaches a saturation point, preventing the error from decreasing. In this case, integral will rapidly
-/// 	> increase, causing an unpredictable (usually much larger than expected) output. This specific implementation
-/// 	> of PID has no guards against integral windup. In cases where this is a problem, a custom implementation
-/// 	> of `FeedbackController` or simply setting `ki` to `0.0` may be desirable
+/// 	> increase, causing an unpredictable (usually much larger than expected) output. By default, this
+/// 	> implementation of PID has no guards against integral windup other than `integral_threshold`. See
+/// 	> [`AntiWindup`] for the available strategies for limiting integral accumulation.
 ///
 /// - The derivative component represents the change in error over time. The derivative component is the
 /// difference between the error given to `update` and the error given to `update` the last time it was
 /// called, multiplied by a constant `kd`. In practice, this component will apply a "damping" effect to the
 /// controller, preventing sudden jerks or changes to the output.
 ///
+/// 	> Since the derivative component reacts to how quickly the error changes, it is sensitive to noise
+/// 	> in the measurement and will produce a large spike ("derivative kick") whenever the setpoint changes.
+/// 	> Setting `derivative_filter` applies a low-pass filter to the derivative to smooth out noise, and
+/// 	> [`DerivativeMode::Measurement`] differentiates the measurement rather than the error to avoid
+/// 	> derivative kick. The latter requires using [`SetpointController::update_with_setpoint`] rather than
+/// 	> [`FeedbackController::update`], since the setpoint and measurement must be known separately.
+///
+/// # Continuous Input
+///
+/// Some systems, such as a robot's heading, have inputs that wrap around at the ends of a range. In these
+/// cases, the shortest path to the setpoint may cross the ends of the range (for example, turning from 170°
+/// to -170° should be a 20° turn, not a 340° turn). Setting `continuous_input` to the range's minimum and
+/// maximum will wrap the error and derivative to take the shortest path.
+///
 /// # Tuning
 ///
 /// Tuning a PID controller requires adjusting the three constants - `kp`, `ki`, and `kd` to allow the
@@ -73,10 +159,107 @@ pub struct PIDController {
     /// The derivative gain constant.
     pub kd: f64,
 
+    /// The maximum error magnitude that will be accumulated into the integral.
     pub integral_threshold: f64,
 
+    /// The strategy used to prevent integral windup.
+    pub anti_windup: AntiWindup,
+
+    /// The time constant of the derivative's low-pass filter in seconds.
+    ///
+    /// Higher values smooth the derivative more heavily at the cost of responsiveness.
+    /// A value of `0.0` disables filtering.
+    pub derivative_filter: f64,
+
+    /// The value that the derivative component is computed from.
+    pub derivative_mode: DerivativeMode,
+
+    /// The minimum and maximum of the input range, if the input wraps around at the ends of
+    /// its range (such as an angle).
+    pub continuous_input: Option<(f64, f64)>,
+
     integral: f64,
+    derivative: f64,
     previous_error: f64,
+    previous_measurement: Option<f64>,
+    diagnostics: PIDDiagnostics,
+}
+
+/// A breakdown of a [`PIDController`]'s most recent update, useful for logging and tuning.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct PIDDiagnostics {
+    /// The proportional component's contribution to the output.
+    pub proportional: f64,
+
+    /// The integral component's contribution to the output.
+    ///
+    /// Since the integral is accumulated in output units, this is also the controller's
+    /// accumulated integral.
+    pub integral: f64,
+
+    /// The derivative component's contribution to the output.
+    pub derivative: f64,
+
+    /// The controller's output, after any saturation limits have been applied.
+    pub output: f64,
+
+    /// The error given to the controller.
+    pub error: f64,
+
+    /// The time since the previous update in seconds.
+    pub dt: f64,
+}
+
+/// A strategy for preventing integral windup in a [`PIDController`].
+///
+/// The integral is stored as the integral component's contribution to the controller's
+/// output, so all limits here are expressed in the same units as the output.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub enum AntiWindup {
+    /// Accumulate the integral without any limits.
+    #[default]
+    None,
+
+    /// Reset the integral to zero whenever the error changes sign.
+    ///
+    /// This prevents the integral from continuing to push the system past its setpoint
+    /// after it has overshot.
+    ZeroCrossingReset,
+
+    /// Limit the integral component's magnitude to a maximum value.
+    IntegralLimit(f64),
+
+    /// Clamp the controller's output between a minimum and maximum value, and stop integrating
+    /// while the output is saturated and the error would push it further into saturation
+    /// ("conditional integration").
+    Clamping {
+        min: f64,
+        max: f64,
+    },
+
+    /// Clamp the controller's output between a minimum and maximum value, and bleed the integral
+    /// back down by the amount the output exceeds those limits, scaled by `tracking_gain`.
+    ///
+    /// Higher tracking gains unwind the integral faster once the output saturates.
+    BackCalculation {
+        min: f64,
+        max: f64,
+        tracking_gain: f64,
+    },
+}
+
+/// The value that a [`PIDController`] computes its derivative component from.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub enum DerivativeMode {
+    /// Differentiate the error.
+    #[default]
+    Error,
+
+    /// Differentiate the negated measurement.
+    ///
+    /// While the setpoint is constant, this is equivalent to [`DerivativeMode::Error`], but does not
+    /// produce a spike in output when the setpoint changes.
+    Measurement,
 }
 
 impl PIDController {
@@ -110,6 +293,150 @@ impl PIDController {
     pub fn set_integral_threshold(&mut self, threshold: f64) {
         self.integral_threshold = threshold;
     }
+
+    /// Get the current anti-windup strategy.
+    pub fn anti_windup(&self) -> AntiWindup {
+        self.anti_windup
+    }
+
+    /// Sets the strategy used to prevent integral windup.
+    pub fn set_anti_windup(&mut self, anti_windup: AntiWindup) {
+        self.anti_windup = anti_windup;
+    }
+
+    /// Get the time constant of the derivative's low-pass filter in seconds.
+    pub fn derivative_filter(&self) -> f64 {
+        self.derivative_filter
+    }
+
+    /// Sets the time constant of the derivative's low-pass filter in seconds.
+    pub fn set_derivative_filter(&mut self, time_constant: f64) {
+        self.derivative_filter = time_constant;
+    }
+
+    /// Get the value that the derivative component is computed from.
+    pub fn derivative_mode(&self) -> DerivativeMode {
+        self.derivative_mode
+    }
+
+    /// Sets the value that the derivative component is computed from.
+    pub fn set_derivative_mode(&mut self, mode: DerivativeMode) {
+        self.derivative_mode = mode;
+    }
+
+    /// Get the minimum and maximum of the input range, if continuous input is enabled.
+    pub fn continuous_input(&self) -> Option<(f64, f64)> {
+        self.continuous_input
+    }
+
+    /// Sets the minimum and maximum of the input range for inputs that wrap around, such as
+    /// angles. Passing `None` disables continuous input.
+    pub fn set_continuous_input(&mut self, range: Option<(f64, f64)>) {
+        self.continuous_input = range;
+    }
+
+    /// Get a breakdown of the controller's most recent update.
+    pub fn diagnostics(&self) -> PIDDiagnostics {
+        self.diagnostics
+    }
+
+    /// Wraps a difference between two inputs to the shortest distance between them if continuous
+    /// input is enabled.
+    fn wrap_difference(&self, difference: f64) -> f64 {
+        match self.continuous_input {
+            Some((min, max)) => {
+                let half_range = (max - min) / 2.0;
+                wrap(difference, -half_range, half_range)
+            }
+            None => difference,
+        }
+    }
+
+    fn calculate(&mut self, error: f64, measurement: f64, dt: Duration) -> f64 {
+        let dt = dt.as_secs_f64();
+        let error = self.wrap_difference(error);
+        let previous_integral = self.integral;
+
+        if error.abs() < self.integral_threshold {
+            self.integral += error * self.ki * dt;
+        }
+
+        match self.anti_windup {
+            AntiWindup::ZeroCrossingReset => {
+                if error.signum() != self.previous_error.signum() {
+                    self.integral = 0.0;
+                }
+            }
+            AntiWindup::IntegralLimit(limit) => {
+                self.integral = self.integral.max(-limit.abs()).min(limit.abs());
+            }
+            _ => {}
+        }
+
+        let raw_derivative = match self.derivative_mode {
+            DerivativeMode::Error => self.wrap_difference(error - self.previous_error) / dt,
+            DerivativeMode::Measurement => match self.previous_measurement {
+                Some(previous_measurement) => {
+                    -self.wrap_difference(measurement - previous_measurement) / dt
+                }
+                None => 0.0,
+            },
+        };
+        self.previous_error = error;
+        self.previous_measurement = Some(measurement);
+
+        // First-order low-pass filter on the derivative.
+        let alpha = if self.derivative_filter > 0.0 {
+            self.derivative_filter / (self.derivative_filter + dt)
+        } else {
+            0.0
+        };
+        self.derivative = alpha * self.derivative + (1.0 - alpha) * raw_derivative;
+
+        let proportional = error * self.kp;
+        let derivative = self.derivative * self.kd;
+        let output = proportional + self.integral + derivative;
+
+        let output = match self.anti_windup {
+            AntiWindup::Clamping { min, max } => {
+                // `f64::clamp` panics if `min > max` or either limit is NaN, so saturate
+                // manually instead.
+                let saturated = output.max(min).min(max);
+
+                // Undo this update's integration if the output is saturated and the error is
+                // pushing it further in the direction of saturation.
+                if saturated != output && error.signum() == output.signum() {
+                    self.integral = previous_integral;
+                }
+
+                (proportional + self.integral + derivative).max(min).min(max)
+            }
+            AntiWindup::BackCalculation {
+                min,
+                max,
+                tracking_gain,
+            } => {
+                let saturated = output.max(min).min(max);
+
+                // Feed the amount of saturation back into the integral to unwind it.
+                self.integral += tracking_gain * (saturated - output) * dt;
+
+                saturated
+            }
+            _ => output,
+        };
+
+        self.diagnostics = PIDDiagnostics {
+            proportional,
+            integral: self.integral,
+            derivative,
+            output,
+            error,
+            dt,
+        };
+
+        output
+    }
 }
 
 impl FeedbackController for PIDController {
@@ -117,17 +444,1045 @@ impl FeedbackController for PIDController {
     type Output = f64;
 
     fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
-        if error.abs() < self.integral_threshold {
-            self.integral += error;
+        // Without a known setpoint, it is assumed to be zero.
+        self.calculate(error, -error, dt)
+    }
+}
+
+impl SetpointController for PIDController {
+    type Input = f64;
+    type Output = f64;
+
+    fn update_with_setpoint(
+        &mut self,
+        setpoint: Self::Input,
+        measurement: Self::Input,
+        dt: Duration,
+    ) -> Self::Output {
+        self.calculate(setpoint - measurement, measurement, dt)
+    }
+}
+
+/// A [`PIDController`] whose gains change depending on the magnitude of the error.
+///
+/// A single set of PID gains is often a compromise between large and small movements. For example, a
+/// turn controller tuned for 180° turns will often be sluggish when correcting small heading errors.
+/// A gain-scheduled controller holds a table of error thresholds and the gains to use at those errors,
+/// and picks gains from the table each time it is updated.
+///
+/// Since the inner [`PIDController`] stores its integral in output units and its derivative before
+/// applying `kd`, changing gains does not cause a sudden jump in the integral or derivative components.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct GainScheduledController {
+    /// The PID controller whose gains are scheduled.
+    pub controller: PIDController,
+
+    /// How gains are chosen between entries in the schedule.
+    pub mode: ScheduleMode,
+
+    schedule: Vec<(f64, (f64, f64, f64))>,
+}
+
+/// How a [`GainScheduledController`] chooses gains from its schedule.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub enum ScheduleMode {
+    /// Use the gains of the entry with the smallest threshold at or above the error's magnitude.
+    #[default]
+    Switch,
+
+    /// Linearly interpolate between the gains of the two entries with thresholds surrounding the
+    /// error's magnitude.
+    Interpolate,
+}
+
+impl GainScheduledController {
+    /// Construct a new [`GainScheduledController`] from a PID controller and a schedule of
+    /// (`error_threshold`, (`kp`, `ki`, `kd`)) entries.
+    ///
+    /// Errors larger than every threshold in the schedule will use the gains of the largest threshold.
+    pub fn new(
+        controller: PIDController,
+        schedule: Vec<(f64, (f64, f64, f64))>,
+        mode: ScheduleMode,
+    ) -> Self {
+        let mut scheduled = Self {
+            controller,
+            mode,
+            schedule: Vec::new(),
+        };
+
+        scheduled.set_schedule(schedule);
+
+        scheduled
+    }
+
+    /// Get the schedule of (`error_threshold`, (`kp`, `ki`, `kd`)) entries, sorted by threshold.
+    pub fn schedule(&self) -> &[(f64, (f64, f64, f64))] {
+        &self.schedule
+    }
+
+    /// Sets the schedule of (`error_threshold`, (`kp`, `ki`, `kd`)) entries.
+    pub fn set_schedule(&mut self, mut schedule: Vec<(f64, (f64, f64, f64))>) {
+        schedule.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        self.schedule = schedule;
+    }
+
+    /// Get the gains that the schedule specifies for a given error.
+    ///
+    /// Returns `None` if the schedule is empty.
+    pub fn scheduled_gains(&self, error: f64) -> Option<(f64, f64, f64)> {
+        let error = error.abs();
+        let index = self
+            .schedule
+            .iter()
+            .position(|(threshold, _)| *threshold >= error)
+            .unwrap_or(self.schedule.len().checked_sub(1)?);
+        let (threshold, gains) = self.schedule[index];
+
+        match self.mode {
+            ScheduleMode::Switch => Some(gains),
+            ScheduleMode::Interpolate => {
+                if index == 0 || error > threshold {
+                    return Some(gains);
+                }
+
+                let (previous_threshold, previous_gains) = self.schedule[index - 1];
+                let t = (error - previous_threshold) / (threshold - previous_threshold);
+
+                Some((
+                    previous_gains.0 + (gains.0 - previous_gains.0) * t,
+                    previous_gains.1 + (gains.1 - previous_gains.1) * t,
+                    previous_gains.2 + (gains.2 - previous_gains.2) * t,
+                ))
+            }
         }
+    }
+
+    fn schedule_gains(&mut self, error: f64) {
+        if let Some(gains) = self.scheduled_gains(error) {
+            self.controller.set_gains(gains);
+        }
+    }
+}
+
+impl FeedbackController for GainScheduledController {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        self.schedule_gains(error);
+        self.controller.update(error, dt)
+    }
+}
+
+impl SetpointController for GainScheduledController {
+    type Input = f64;
+    type Output = f64;
+
+    fn update_with_setpoint(
+        &mut self,
+        setpoint: Self::Input,
+        measurement: Self::Input,
+        dt: Duration,
+    ) -> Self::Output {
+        self.schedule_gains(setpoint - measurement);
+        self.controller.update_with_setpoint(setpoint, measurement, dt)
+    }
+}
+
+/// A take-back-half (TBH) velocity controller.
+///
+/// Take-back-half is an integrating controller commonly used to control the velocity of flywheels.
+/// Each update, the error multiplied by `gain` is accumulated into the output. Whenever the error changes
+/// sign (meaning the velocity has crossed the setpoint), the output is "taken back" to halfway between its
+/// current value and the value it had at the previous crossing. This causes the output to quickly converge
+/// on the value needed to hold the setpoint without the overshoot of a pure integral controller.
+///
+/// Since the output needed to hold a velocity is usually predictable, an initial guess of the output
+/// can be provided, which will be used at the first crossing to speed up convergence.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct TakeBackHalfController {
+    /// The integral gain constant.
+    pub gain: f64,
+
+    /// The estimated output needed to hold the setpoint, used at the first crossing.
+    pub initial_output: f64,
+
+    output: f64,
+    tbh: f64,
+    previous_error: f64,
+    first_crossing: bool,
+}
+
+impl TakeBackHalfController {
+    /// Construct a new [`TakeBackHalfController`] from a gain constant and initial output guess.
+    pub fn new(gain: f64, initial_output: f64) -> Self {
+        Self {
+            gain,
+            initial_output,
+            tbh: initial_output,
+            first_crossing: true,
+            ..Default::default()
+        }
+    }
+
+    /// Get the current gain constant.
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    /// Sets the gain constant to a provided value.
+    pub fn set_gain(&mut self, gain: f64) {
+        self.gain = gain;
+    }
+
+    /// Resets the controller's output and crossing state, such as after changing setpoints.
+    pub fn reset(&mut self) {
+        self.output = 0.0;
+        self.tbh = self.initial_output;
+        self.previous_error = 0.0;
+        self.first_crossing = true;
+    }
+}
+
+impl FeedbackController for TakeBackHalfController {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        self.output += error * self.gain * dt.as_secs_f64();
 
         if error.signum() != self.previous_error.signum() {
-            self.integral = 0.0;
+            if self.first_crossing {
+                self.output = self.initial_output;
+                self.first_crossing = false;
+            } else {
+                self.output = (self.output + self.tbh) / 2.0;
+            }
+
+            self.tbh = self.output;
         }
-        
-        let derivative = error - self.previous_error;
+
         self.previous_error = error;
 
-        (error * self.kp) + (self.integral * self.ki * dt.as_secs_f64()) + (derivative * self.kd / dt.as_secs_f64())
+        self.output
     }
-}
\ No newline at end of file
+}
+
+/// A bang-bang feedback controller.
+///
+/// Bang-bang control switches the output between two values depending on which side of the setpoint
+/// the system is on. It outputs `high` when the error is positive and `low` when the error is negative.
+/// This is the fastest possible way for a system like a flywheel to recover from a disturbance, but will
+/// oscillate around the setpoint.
+///
+/// To prevent the output from rapidly switching back and forth when the error is close to zero, a
+/// `hysteresis` band can be provided. While the error's magnitude is within this band, the controller
+/// will continue producing its previous output.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct BangBangController {
+    /// The output produced when the error is positive.
+    pub high: f64,
+
+    /// The output produced when the error is negative.
+    pub low: f64,
+
+    /// The error magnitude within which the output will not switch.
+    pub hysteresis: f64,
+
+    output: f64,
+}
+
+impl BangBangController {
+    /// Construct a new [`BangBangController`] from its two outputs and a hysteresis band.
+    pub fn new(high: f64, low: f64, hysteresis: f64) -> Self {
+        Self {
+            high,
+            low,
+            hysteresis,
+            output: low,
+        }
+    }
+}
+
+impl FeedbackController for BangBangController {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, _dt: Duration) -> Self::Output {
+        if error > self.hysteresis {
+            self.output = self.high;
+        } else if error < -self.hysteresis {
+            self.output = self.low;
+        }
+
+        self.output
+    }
+}
+
+/// An open-loop feedforward controller.
+///
+/// Unlike a [`FeedbackController`], a feedforward controller does not measure the system it controls.
+/// Instead, it uses a model of the system to predict the output needed to reach a desired state
+/// (a "reference"), described by a position, velocity, and acceleration. Feedforward is most effective
+/// when combined with a feedback controller that corrects for error left over from an imperfect model
+/// (see [`WithFeedforward`]).
+pub trait Feedforward: Send + Sync + 'static {
+    /// Produce an output value predicted to bring the system to a reference `position`,
+    /// `velocity`, and `acceleration`.
+    fn calculate(&self, position: f64, velocity: f64, acceleration: f64) -> f64;
+}
+
+/// Returns the direction of a velocity, or zero if the velocity is zero.
+///
+/// This is used for static friction terms, which should not be applied when stationary.
+fn velocity_sign(velocity: f64) -> f64 {
+    if velocity == 0.0 {
+        0.0
+    } else {
+        velocity.signum()
+    }
+}
+
+/// A feedforward model for a permanent-magnet DC motor with no external load, such as a flywheel
+/// or drivetrain side.
+///
+/// The output is computed as `ks * sign(v) + kv * v + ka * a`.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct SimpleMotorFeedforward {
+    /// The static gain constant, which overcomes static friction.
+    pub ks: f64,
+
+    /// The velocity gain constant.
+    pub kv: f64,
+
+    /// The acceleration gain constant.
+    pub ka: f64,
+}
+
+impl SimpleMotorFeedforward {
+    /// Construct a new [`SimpleMotorFeedforward`] from gain constants.
+    pub fn new(ks: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kv, ka }
+    }
+}
+
+impl Feedforward for SimpleMotorFeedforward {
+    fn calculate(&self, _position: f64, velocity: f64, acceleration: f64) -> f64 {
+        self.ks * velocity_sign(velocity) + self.kv * velocity + self.ka * acceleration
+    }
+}
+
+/// A feedforward model for a rotating arm acted upon by gravity.
+///
+/// The output is computed as `ks * sign(v) + kg * cos(θ) + kv * v + ka * a`, where `θ` is the reference
+/// position in radians measured from horizontal.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct ArmFeedforward {
+    /// The static gain constant, which overcomes static friction.
+    pub ks: f64,
+
+    /// The gravity gain constant, which holds the arm level against gravity.
+    pub kg: f64,
+
+    /// The velocity gain constant.
+    pub kv: f64,
+
+    /// The acceleration gain constant.
+    pub ka: f64,
+}
+
+impl ArmFeedforward {
+    /// Construct a new [`ArmFeedforward`] from gain constants.
+    pub fn new(ks: f64, kg: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kg, kv, ka }
+    }
+}
+
+impl Feedforward for ArmFeedforward {
+    fn calculate(&self, position: f64, velocity: f64, acceleration: f64) -> f64 {
+        self.ks * velocity_sign(velocity)
+            + self.kg * position.cos()
+            + self.kv * velocity
+            + self.ka * acceleration
+    }
+}
+
+/// A feedforward model for a linear elevator acted upon by gravity.
+///
+/// The output is computed as `ks * sign(v) + kg + kv * v + ka * a`.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct ElevatorFeedforward {
+    /// The static gain constant, which overcomes static friction.
+    pub ks: f64,
+
+    /// The gravity gain constant, which holds the elevator in place against gravity.
+    pub kg: f64,
+
+    /// The velocity gain constant.
+    pub kv: f64,
+
+    /// The acceleration gain constant.
+    pub ka: f64,
+}
+
+impl ElevatorFeedforward {
+    /// Construct a new [`ElevatorFeedforward`] from gain constants.
+    pub fn new(ks: f64, kg: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kg, kv, ka }
+    }
+}
+
+impl Feedforward for ElevatorFeedforward {
+    fn calculate(&self, _position: f64, velocity: f64, acceleration: f64) -> f64 {
+        self.ks * velocity_sign(velocity) + self.kg + self.kv * velocity + self.ka * acceleration
+    }
+}
+
+/// A [`FeedbackController`] combined with a [`Feedforward`] model.
+///
+/// The output of this controller is the sum of the feedback controller's correction and the
+/// feedforward model's prediction for the current reference state. The reference should be updated
+/// with [`WithFeedforward::set_reference`] whenever the desired state changes, such as when following
+/// a velocity setpoint or motion profile.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct WithFeedforward<C: FeedbackController<Input = f64, Output = f64>, F: Feedforward> {
+    /// The feedback controller correcting for error.
+    pub controller: C,
+
+    /// The feedforward model predicting output from the reference state.
+    pub feedforward: F,
+
+    reference: (f64, f64, f64),
+}
+
+impl<C: FeedbackController<Input = f64, Output = f64>, F: Feedforward> WithFeedforward<C, F> {
+    /// Construct a new [`WithFeedforward`] from a feedback controller and feedforward model.
+    pub fn new(controller: C, feedforward: F) -> Self {
+        Self {
+            controller,
+            feedforward,
+            reference: (0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Get the current reference state as a tuple (`position`, `velocity`, `acceleration`).
+    pub fn reference(&self) -> (f64, f64, f64) {
+        self.reference
+    }
+
+    /// Sets the reference state that the feedforward model will predict output for.
+    pub fn set_reference(&mut self, position: f64, velocity: f64, acceleration: f64) {
+        self.reference = (position, velocity, acceleration);
+    }
+}
+
+impl<C: FeedbackController<Input = f64, Output = f64>, F: Feedforward> FeedbackController
+    for WithFeedforward<C, F>
+{
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        let (position, velocity, acceleration) = self.reference;
+
+        self.controller.update(error, dt) + self.feedforward.calculate(position, velocity, acceleration)
+    }
+}
+
+/// Extension methods for combining and modifying [`FeedbackController`]s.
+///
+/// Each method wraps the controller in a new type that also implements [`FeedbackController`],
+/// so combinators can be chained and used anywhere a controller is expected.
+///
+/// # Example
+///
+/// ```
+/// let controller = PIDController::new((3.0, 0.0, 0.0), 0.3)
+///     .with_deadband(0.5)
+///     .slew_limited(240.0)
+///     .clamped(-100.0, 100.0);
+/// ```
+pub trait FeedbackControllerExt: FeedbackController + Sized {
+    /// Clamp this controller's output between a minimum and maximum value.
+    fn clamped(self, min: f64, max: f64) -> Clamped<Self>
+    where
+        Self: FeedbackController<Output = f64>,
+    {
+        Clamped::new(self, min, max)
+    }
+
+    /// Produce zero output while the magnitude of this controller's output is less than `deadband`.
+    fn with_deadband(self, deadband: f64) -> Deadband<Self>
+    where
+        Self: FeedbackController<Output = f64>,
+    {
+        Deadband::new(self, deadband)
+    }
+
+    /// Limit how quickly this controller's output can change, in output units per second.
+    fn slew_limited(self, rate: f64) -> SlewLimited<Self>
+    where
+        Self: FeedbackController<Output = f64>,
+    {
+        SlewLimited::new(self, rate)
+    }
+
+    /// Sum the outputs of this controller and another controller given the same error.
+    fn plus<C: FeedbackController<Input = Self::Input>>(self, other: C) -> Sum<Self, C>
+    where
+        Self::Input: Clone,
+        Self::Output: Add<C::Output>,
+    {
+        Sum(self, other)
+    }
+
+    /// Transform this controller's output using a function.
+    fn map<O, F: Fn(Self::Output) -> O + Send + Sync + 'static>(self, f: F) -> Map<Self, F> {
+        Map(self, f)
+    }
+
+    /// Add the output of a [`Feedforward`] model to this controller's output.
+    fn with_feedforward<F: Feedforward>(self, feedforward: F) -> WithFeedforward<Self, F>
+    where
+        Self: FeedbackController<Input = f64, Output = f64>,
+    {
+        WithFeedforward::new(self, feedforward)
+    }
+}
+
+impl<C: FeedbackController> FeedbackControllerExt for C {}
+
+/// A [`FeedbackController`] with its output clamped between a minimum and maximum value.
+///
+/// See [`FeedbackControllerExt::clamped`].
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct Clamped<C: FeedbackController<Output = f64>> {
+    /// The wrapped controller.
+    pub controller: C,
+
+    /// The minimum output value.
+    pub min: f64,
+
+    /// The maximum output value.
+    pub max: f64,
+}
+
+impl<C: FeedbackController<Output = f64>> Clamped<C> {
+    /// Construct a new [`Clamped`] controller from a controller and output limits.
+    pub fn new(controller: C, min: f64, max: f64) -> Self {
+        Self {
+            controller,
+            min,
+            max,
+        }
+    }
+}
+
+impl<C: FeedbackController<Output = f64>> FeedbackController for Clamped<C> {
+    type Input = C::Input;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        // Unlike `f64::clamp`, this doesn't panic if `min > max` or either limit is NaN.
+        self.controller.update(error, dt).max(self.min).min(self.max)
+    }
+}
+
+/// A [`FeedbackController`] that produces no output while its output's magnitude is within a deadband.
+///
+/// This is useful for preventing a mechanism from being driven with outputs too small to move it.
+/// See [`FeedbackControllerExt::with_deadband`].
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct Deadband<C: FeedbackController<Output = f64>> {
+    /// The wrapped controller.
+    pub controller: C,
+
+    /// The output magnitude below which zero will be output.
+    pub deadband: f64,
+}
+
+impl<C: FeedbackController<Output = f64>> Deadband<C> {
+    /// Construct a new [`Deadband`] controller from a controller and deadband.
+    pub fn new(controller: C, deadband: f64) -> Self {
+        Self {
+            controller,
+            deadband,
+        }
+    }
+}
+
+impl<C: FeedbackController<Output = f64>> FeedbackController for Deadband<C> {
+    type Input = C::Input;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        let output = self.controller.update(error, dt);
+
+        if output.abs() < self.deadband {
+            0.0
+        } else {
+            output
+        }
+    }
+}
+
+/// A [`FeedbackController`] with a limit on how quickly its output can change.
+///
+/// Limiting the rate of change of a drivetrain's output helps prevent wheel slip and tipping
+/// during sudden accelerations. See [`FeedbackControllerExt::slew_limited`].
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct SlewLimited<C: FeedbackController<Output = f64>> {
+    /// The wrapped controller.
+    pub controller: C,
+
+    /// The maximum change in output per second.
+    pub rate: f64,
+
+    previous_output: f64,
+}
+
+impl<C: FeedbackController<Output = f64>> SlewLimited<C> {
+    /// Construct a new [`SlewLimited`] controller from a controller and maximum rate of change.
+    pub fn new(controller: C, rate: f64) -> Self {
+        Self {
+            controller,
+            rate,
+            previous_output: 0.0,
+        }
+    }
+}
+
+impl<C: FeedbackController<Output = f64>> FeedbackController for SlewLimited<C> {
+    type Input = C::Input;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        let max_change = self.rate.abs() * dt.as_secs_f64();
+        let output = self
+            .controller
+            .update(error, dt)
+            .max(self.previous_output - max_change)
+            .min(self.previous_output + max_change);
+
+        self.previous_output = output;
+
+        output
+    }
+}
+
+/// Two [`FeedbackController`]s given the same error with their outputs summed.
+///
+/// See [`FeedbackControllerExt::plus`].
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct Sum<A: FeedbackController, B: FeedbackController<Input = A::Input>>(pub A, pub B);
+
+impl<A: FeedbackController, B: FeedbackController<Input = A::Input>> FeedbackController for Sum<A, B>
+where
+    A::Input: Clone,
+    A::Output: Add<B::Output>,
+{
+    type Input = A::Input;
+    type Output = <A::Output as Add<B::Output>>::Output;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        self.0.update(error.clone(), dt) + self.1.update(error, dt)
+    }
+}
+
+/// A [`FeedbackController`] with its output transformed by a function.
+///
+/// See [`FeedbackControllerExt::map`].
+#[derive(Clone, Debug, Copy, Default)]
+pub struct Map<C: FeedbackController, F>(pub C, pub F);
+
+impl<C: FeedbackController, O, F: Fn(C::Output) -> O + Send + Sync + 'static> FeedbackController
+    for Map<C, F>
+{
+    type Input = C::Input;
+    type Output = O;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        (self.1)(self.0.update(error, dt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn integral_limit_caps_integral() {
+        let mut pid = PIDController::new((0.0, 10.0, 0.0), f64::MAX);
+        pid.set_anti_windup(AntiWindup::IntegralLimit(0.5));
+
+        for _ in 0..100 {
+            pid.update(1.0, DT);
+        }
+
+        assert_eq!(pid.diagnostics().integral, 0.5);
+    }
+
+    #[test]
+    fn zero_crossing_resets_integral() {
+        let mut pid = PIDController::new((0.0, 1.0, 0.0), f64::MAX);
+        pid.set_anti_windup(AntiWindup::ZeroCrossingReset);
+
+        for _ in 0..10 {
+            pid.update(1.0, DT);
+        }
+        assert!(pid.diagnostics().integral > 0.0);
+
+        pid.update(-1.0, DT);
+        assert_eq!(pid.diagnostics().integral, 0.0);
+    }
+
+    #[test]
+    fn clamping_stops_integrating_while_saturated() {
+        let mut pid = PIDController::new((1.0, 1.0, 0.0), f64::MAX);
+        pid.set_anti_windup(AntiWindup::Clamping {
+            min: -1.0,
+            max: 1.0,
+        });
+
+        for _ in 0..100 {
+            assert!(pid.update(5.0, DT) <= 1.0);
+        }
+
+        // The proportional term alone saturates the output, so nothing is integrated.
+        assert_eq!(pid.diagnostics().integral, 0.0);
+    }
+
+    #[test]
+    fn back_calculation_unwinds_integral() {
+        let mut unlimited = PIDController::new((1.0, 1.0, 0.0), f64::MAX);
+        let mut pid = unlimited;
+        pid.set_anti_windup(AntiWindup::BackCalculation {
+            min: -1.0,
+            max: 1.0,
+            tracking_gain: 10.0,
+        });
+
+        for _ in 0..100 {
+            unlimited.update(2.0, DT);
+            assert!(pid.update(2.0, DT) <= 1.0);
+        }
+
+        assert!(pid.diagnostics().integral < unlimited.diagnostics().integral);
+    }
+
+    #[test]
+    fn inverted_or_nan_limits_do_not_panic() {
+        for (min, max) in [(1.0, -1.0), (f64::NAN, 1.0), (-1.0, f64::NAN)] {
+            let mut clamping = PIDController::new((1.0, 1.0, 0.0), f64::MAX);
+            clamping.set_anti_windup(AntiWindup::Clamping { min, max });
+            clamping.update(1.0, DT);
+
+            let mut back_calculation = PIDController::new((1.0, 1.0, 0.0), f64::MAX);
+            back_calculation.set_anti_windup(AntiWindup::BackCalculation {
+                min,
+                max,
+                tracking_gain: 1.0,
+            });
+            back_calculation.update(1.0, DT);
+        }
+
+        let mut limited = PIDController::new((1.0, 1.0, 0.0), f64::MAX);
+        limited.set_anti_windup(AntiWindup::IntegralLimit(f64::NAN));
+        limited.update(1.0, DT);
+    }
+
+    #[test]
+    fn simple_motor_feedforward_skips_static_term_at_rest() {
+        let feedforward = SimpleMotorFeedforward::new(1.0, 2.0, 3.0);
+
+        assert_eq!(feedforward.calculate(0.0, 0.0, 0.0), 0.0);
+        assert_eq!(feedforward.calculate(0.0, 0.0, 1.0), 3.0);
+        assert_eq!(feedforward.calculate(0.0, 2.0, 1.0), 1.0 + 4.0 + 3.0);
+        assert_eq!(feedforward.calculate(0.0, -2.0, 0.0), -1.0 - 4.0);
+    }
+
+    #[test]
+    fn arm_feedforward_scales_gravity_with_angle() {
+        let feedforward = ArmFeedforward::new(0.0, 2.0, 0.0, 0.0);
+
+        assert_eq!(feedforward.calculate(0.0, 0.0, 0.0), 2.0);
+        assert!(feedforward.calculate(core::f64::consts::FRAC_PI_2, 0.0, 0.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn elevator_feedforward_always_holds_gravity() {
+        let feedforward = ElevatorFeedforward::new(0.5, 2.0, 1.0, 0.0);
+
+        assert_eq!(feedforward.calculate(10.0, 0.0, 0.0), 2.0);
+        assert_eq!(feedforward.calculate(10.0, 1.0, 0.0), 3.5);
+    }
+
+    #[test]
+    fn with_feedforward_adds_reference_prediction() {
+        let mut controller = WithFeedforward::new(
+            PIDController::new((2.0, 0.0, 0.0), 0.0),
+            SimpleMotorFeedforward::new(0.0, 1.0, 0.0),
+        );
+
+        assert_eq!(controller.update(1.0, DT), 2.0);
+
+        controller.set_reference(0.0, 5.0, 0.0);
+        assert_eq!(controller.update(1.0, DT), 7.0);
+    }
+
+    #[test]
+    fn take_back_half_uses_initial_output_then_halves() {
+        let mut tbh = TakeBackHalfController::new(100.0, 5.0);
+
+        assert_eq!(tbh.update(1.0, DT), 1.0);
+
+        // The first crossing jumps straight to the initial guess.
+        assert_eq!(tbh.update(-1.0, DT), 5.0);
+        assert_eq!(tbh.update(-1.0, DT), 4.0);
+
+        // Later crossings take back half of the change since the previous crossing.
+        assert_eq!(tbh.update(1.0, DT), (5.0 + 5.0) / 2.0);
+
+        tbh.reset();
+        assert_eq!(tbh.update(1.0, DT), 1.0);
+        assert_eq!(tbh.update(-1.0, DT), 5.0);
+    }
+
+    #[test]
+    fn take_back_half_converges_on_flywheel() {
+        let mut tbh = TakeBackHalfController::new(2.0, 0.0);
+        let mut velocity = 0.0;
+
+        // A first-order flywheel whose steady-state velocity equals its input.
+        for _ in 0..2000 {
+            let output = tbh.update(50.0 - velocity, DT);
+            velocity += (output - velocity) * 5.0 * DT.as_secs_f64();
+        }
+
+        assert!((velocity - 50.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn bang_bang_holds_output_within_hysteresis() {
+        let mut bang_bang = BangBangController::new(10.0, -10.0, 1.0);
+
+        assert_eq!(bang_bang.update(0.5, DT), -10.0);
+        assert_eq!(bang_bang.update(2.0, DT), 10.0);
+        assert_eq!(bang_bang.update(0.5, DT), 10.0);
+        assert_eq!(bang_bang.update(-0.5, DT), 10.0);
+        assert_eq!(bang_bang.update(-2.0, DT), -10.0);
+    }
+
+    #[test]
+    fn derivative_filter_smooths_steps() {
+        let mut unfiltered = PIDController::new((0.0, 0.0, 1.0), 0.0);
+        let mut filtered = unfiltered;
+        filtered.set_derivative_filter(0.09);
+
+        unfiltered.update(0.0, DT);
+        filtered.update(0.0, DT);
+
+        let unfiltered_output = unfiltered.update(1.0, DT);
+        let filtered_output = filtered.update(1.0, DT);
+
+        assert!((unfiltered_output - 100.0).abs() < 1e-9);
+        assert!((filtered_output - 10.0).abs() < 1e-9);
+
+        // The filtered derivative decays rather than dropping straight to zero.
+        assert_eq!(unfiltered.update(1.0, DT), 0.0);
+        assert!((filtered.update(1.0, DT) - 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn derivative_on_measurement_avoids_kick() {
+        let mut on_error = PIDController::new((0.0, 0.0, 1.0), 0.0);
+        let mut on_measurement = on_error;
+        on_measurement.set_derivative_mode(DerivativeMode::Measurement);
+
+        on_error.update_with_setpoint(0.0, 0.0, DT);
+        on_measurement.update_with_setpoint(0.0, 0.0, DT);
+
+        // Changing the setpoint kicks the derivative on error, but not on measurement.
+        assert!(on_error.update_with_setpoint(10.0, 0.0, DT) > 0.0);
+        assert_eq!(on_measurement.update_with_setpoint(10.0, 0.0, DT), 0.0);
+
+        // Moving towards the setpoint is damped.
+        assert!(on_measurement.update_with_setpoint(10.0, 1.0, DT) < 0.0);
+    }
+
+    #[test]
+    fn derivative_on_measurement_starts_at_zero() {
+        let mut pid = PIDController::new((0.0, 0.0, 1.0), 0.0);
+        pid.set_derivative_mode(DerivativeMode::Measurement);
+
+        assert_eq!(pid.update_with_setpoint(0.0, 5.0, DT), 0.0);
+    }
+
+    #[test]
+    fn error_adapter_passes_difference() {
+        let mut adapter = ErrorAdapter(PIDController::new((2.0, 0.0, 0.0), 0.0));
+
+        assert_eq!(adapter.update_with_setpoint(5.0, 3.0, DT), 4.0);
+    }
+
+    #[test]
+    fn setpoint_adapter_matches_known_constant_setpoint() {
+        let mut pid = PIDController::new((2.0, 0.0, 0.1), 0.0);
+        pid.set_derivative_mode(DerivativeMode::Measurement);
+        let mut adapter = SetpointAdapter(pid);
+
+        for measurement in [0.0, 1.0, 3.0, 4.0] {
+            let expected = pid.update_with_setpoint(5.0, measurement, DT);
+            let output = adapter.update(5.0 - measurement, DT);
+
+            assert!((output - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn clamped_limits_output() {
+        let mut clamped = PIDController::new((2.0, 0.0, 0.0), 0.0).clamped(-1.0, 1.0);
+
+        assert_eq!(clamped.update(10.0, DT), 1.0);
+        assert_eq!(clamped.update(-10.0, DT), -1.0);
+        assert_eq!(clamped.update(0.25, DT), 0.5);
+    }
+
+    #[test]
+    fn clamped_tolerates_invalid_limits() {
+        let mut inverted = PIDController::new((1.0, 0.0, 0.0), 0.0).clamped(1.0, -1.0);
+        let mut nan = PIDController::new((1.0, 0.0, 0.0), 0.0).clamped(f64::NAN, f64::NAN);
+
+        assert!(inverted.update(5.0, DT).is_finite());
+        assert_eq!(nan.update(5.0, DT), 5.0);
+    }
+
+    #[test]
+    fn deadband_zeroes_small_outputs() {
+        let mut deadband = PIDController::new((1.0, 0.0, 0.0), 0.0).with_deadband(0.5);
+
+        assert_eq!(deadband.update(0.25, DT), 0.0);
+        assert_eq!(deadband.update(-0.25, DT), 0.0);
+        assert_eq!(deadband.update(2.0, DT), 2.0);
+    }
+
+    #[test]
+    fn slew_limited_ramps_output() {
+        let mut slew = PIDController::new((1.0, 0.0, 0.0), 0.0).slew_limited(100.0);
+
+        // 100 units per second is 1 unit per 10 ms update.
+        assert!((slew.update(10.0, DT) - 1.0).abs() < 1e-9);
+        assert!((slew.update(10.0, DT) - 2.0).abs() < 1e-9);
+        assert!((slew.update(-10.0, DT) - 1.0).abs() < 1e-9);
+        assert!((slew.update(1.0, DT) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn plus_and_map_combine_outputs() {
+        let mut sum = PIDController::new((1.0, 0.0, 0.0), 0.0)
+            .plus(PIDController::new((2.0, 0.0, 0.0), 0.0))
+            .map(|output| output * 10.0);
+
+        assert_eq!(sum.update(1.0, DT), 30.0);
+    }
+
+    fn schedule() -> Vec<(f64, (f64, f64, f64))> {
+        alloc::vec![
+            (10.0, (1.0, 0.0, 0.0)),
+            (1.0, (0.5, 0.0, 0.0)),
+            (100.0, (2.0, 0.0, 0.0)),
+        ]
+    }
+
+    #[test]
+    fn switch_schedule_picks_next_threshold() {
+        let scheduled = GainScheduledController::new(
+            PIDController::default(),
+            schedule(),
+            ScheduleMode::Switch,
+        );
+
+        assert_eq!(scheduled.schedule()[0].0, 1.0);
+        assert_eq!(scheduled.scheduled_gains(0.5), Some((0.5, 0.0, 0.0)));
+        assert_eq!(scheduled.scheduled_gains(-5.0), Some((1.0, 0.0, 0.0)));
+        assert_eq!(scheduled.scheduled_gains(10.0), Some((1.0, 0.0, 0.0)));
+        assert_eq!(scheduled.scheduled_gains(500.0), Some((2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn interpolated_schedule_blends_gains() {
+        let scheduled = GainScheduledController::new(
+            PIDController::default(),
+            schedule(),
+            ScheduleMode::Interpolate,
+        );
+
+        assert_eq!(scheduled.scheduled_gains(0.5), Some((0.5, 0.0, 0.0)));
+        assert_eq!(scheduled.scheduled_gains(5.5), Some((0.75, 0.0, 0.0)));
+        assert_eq!(scheduled.scheduled_gains(500.0), Some((2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn scheduled_controller_uses_scheduled_gains() {
+        let pid = PIDController::new((3.0, 0.0, 0.0), 0.0);
+
+        let mut scheduled = GainScheduledController::new(pid, schedule(), ScheduleMode::Switch);
+        assert_eq!(scheduled.update(5.0, DT), 5.0);
+
+        let mut empty = GainScheduledController::new(pid, Vec::new(), ScheduleMode::Switch);
+        assert_eq!(empty.scheduled_gains(5.0), None);
+        assert_eq!(empty.update(5.0, DT), 15.0);
+    }
+
+    #[test]
+    fn continuous_input_takes_shortest_path() {
+        let mut pid = PIDController::new((1.0, 0.0, 0.0), 0.0);
+        pid.set_continuous_input(Some((-180.0, 180.0)));
+
+        assert!((pid.update_with_setpoint(-170.0, 170.0, DT) - 20.0).abs() < 1e-9);
+        assert!((pid.update_with_setpoint(170.0, -170.0, DT) + 20.0).abs() < 1e-9);
+        assert!((pid.update(350.0, DT) + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn continuous_input_wraps_derivative() {
+        let mut pid = PIDController::new((0.0, 0.0, 1.0), 0.0);
+        pid.set_continuous_input(Some((-180.0, 180.0)));
+        pid.set_derivative_mode(DerivativeMode::Measurement);
+
+        pid.update_with_setpoint(0.0, 179.0, DT);
+
+        // Crossing from 179 to -179 is a 2 unit change, not a 358 unit one.
+        let output = pid.update_with_setpoint(0.0, -179.0, DT);
+        assert!((output + 200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn diagnostics_break_down_last_update() {
+        let mut pid = PIDController::new((2.0, 10.0, 0.5), f64::MAX);
+        pid.set_anti_windup(AntiWindup::Clamping {
+            min: -1.0,
+            max: 1.0,
+        });
+
+        assert_eq!(pid.diagnostics(), PIDDiagnostics::default());
+
+        let output = pid.update(0.25, DT);
+        let diagnostics = pid.diagnostics();
+
+        assert_eq!(diagnostics.error, 0.25);
+        assert_eq!(diagnostics.dt, 0.01);
+        assert_eq!(diagnostics.proportional, 0.5);
+        assert!((diagnostics.derivative - 12.5).abs() < 1e-9);
+
+        // The output saturated, so conditional integration discarded this update's integral.
+        assert_eq!(diagnostics.integral, 0.0);
+
+        // The reported output is the saturated output, not the sum of the terms.
+        assert_eq!(diagnostics.output, output);
+        assert_eq!(output, 1.0);
+    }
+}
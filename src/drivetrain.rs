@@ -1,5 +1,7 @@
-use alloc::{sync::Arc, vec::Vec};
+use alloc::{boxed::Box, collections::VecDeque, sync::Arc, vec::Vec};
 use core::{
+    f64::consts::{FRAC_PI_2, PI},
+    fmt::{self, Debug, Formatter},
     ops::Drop,
     sync::atomic::{AtomicBool, Ordering},
     time::Duration,
@@ -10,10 +12,16 @@ use vex_rt::rtos::{Instant, Loop, Mutex, Task};
 #[allow(unused_imports)]
 use vex_rt::io::*;
 
+pub use crate::chassis::ChassisModel;
+
 use crate::{
-    controller::FeedbackController,
+    controller::{FeedbackController, Feedforward, SimpleMotorFeedforward},
     devices::MotorGroup,
-    math::{normalize_angle, normalize_motor_power, LineCircleIntersections, Vec2},
+    math::{
+        normalize_angle, normalize_motor_power, PathProgress, PurePursuit, PursuitConstraints,
+        PursuitPath, Stanley, Vec2,
+    },
+    motion_profile::{MotionProfile, MotionState, SCurveProfile, TrapezoidalProfile},
     timer::Timer,
     tracking::Tracking,
 };
@@ -21,8 +29,19 @@ use crate::{
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum DrivetrainTarget {
     Point(Vec2),
+    Pose(Vec2, f64, f64),
+    Path(Vec2),
     DistanceAndHeading(f64, f64),
+    ProfiledDistanceAndHeading {
+        start_distance: f64,
+        start_heading: f64,
+        axis: MotionAxis,
+        profile: AxisProfile,
+        feedforward: SimpleMotorFeedforward,
+        start_time: Instant,
+    },
     MotorPower(f64, f64),
+    ChassisVelocity(f64, f64),
 }
 
 impl Default for DrivetrainTarget {
@@ -31,6 +50,473 @@ impl Default for DrivetrainTarget {
     }
 }
 
+impl DrivetrainTarget {
+    /// The final forward travel and heading targeted, if this target has them.
+    fn distance_and_heading(&self) -> Option<(f64, f64)> {
+        match *self {
+            DrivetrainTarget::DistanceAndHeading(distance, heading) => Some((distance, heading)),
+            DrivetrainTarget::ProfiledDistanceAndHeading {
+                start_distance,
+                start_heading,
+                axis,
+                profile,
+                ..
+            } => Some(match axis {
+                MotionAxis::Drive => (start_distance + profile.distance(), start_heading),
+                MotionAxis::Turn => (start_distance, start_heading + profile.distance()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The axis of motion that a drivetrain motion moves along.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum MotionAxis {
+    #[default]
+    Drive,
+    Turn,
+}
+
+/// A motion profile generated from [`ProfileConstraints`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AxisProfile {
+    Trapezoidal(TrapezoidalProfile),
+    SCurve(SCurveProfile),
+}
+
+impl AxisProfile {
+    fn distance(&self) -> f64 {
+        match self {
+            AxisProfile::Trapezoidal(profile) => profile.distance(),
+            AxisProfile::SCurve(profile) => profile.distance(),
+        }
+    }
+
+    fn duration(&self) -> Duration {
+        match self {
+            AxisProfile::Trapezoidal(profile) => profile.duration(),
+            AxisProfile::SCurve(profile) => profile.duration(),
+        }
+    }
+
+    fn sample(&self, time: Duration) -> MotionState {
+        match self {
+            AxisProfile::Trapezoidal(profile) => profile.sample(time),
+            AxisProfile::SCurve(profile) => profile.sample(time),
+        }
+    }
+}
+
+/// Limits used to generate motion profiles for drivetrain motions.
+///
+/// When a drivetrain has profile constraints set for an axis (see [`DifferentialDrivetrain::set_drive_profile`]
+/// and [`DifferentialDrivetrain::set_turn_profile`]), motions along that axis will move their setpoint along
+/// a motion profile rather than jumping straight to the final target. The feedforward model is given the
+/// profile's velocity and acceleration and added to the feedback controller's output.
+///
+/// Limits that are zero or NaN (such as those of [`ProfileConstraints::default`]) can't be profiled, so
+/// motions using them jump straight to their final target as if profiling were disabled.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct ProfileConstraints {
+    /// The maximum velocity of the profile.
+    pub max_velocity: f64,
+
+    /// The maximum acceleration of the profile.
+    pub max_acceleration: f64,
+
+    /// The maximum jerk of the profile. If `None`, a [`TrapezoidalProfile`] is used, otherwise
+    /// an [`SCurveProfile`] is used.
+    pub max_jerk: Option<f64>,
+
+    /// The feedforward model converting the profile's velocity and acceleration into motor power.
+    pub feedforward: SimpleMotorFeedforward,
+}
+
+impl ProfileConstraints {
+    /// Construct a new set of [`ProfileConstraints`].
+    pub fn new(
+        max_velocity: f64,
+        max_acceleration: f64,
+        max_jerk: Option<f64>,
+        feedforward: SimpleMotorFeedforward,
+    ) -> Self {
+        Self {
+            max_velocity,
+            max_acceleration,
+            max_jerk,
+            feedforward,
+        }
+    }
+
+    fn profile(&self, distance: f64) -> AxisProfile {
+        match self.max_jerk {
+            Some(max_jerk) => AxisProfile::SCurve(SCurveProfile::new(
+                distance,
+                self.max_velocity,
+                self.max_acceleration,
+                max_jerk,
+            )),
+            None => AxisProfile::Trapezoidal(TrapezoidalProfile::new(
+                distance,
+                self.max_velocity,
+                self.max_acceleration,
+            )),
+        }
+    }
+}
+
+/// The method used by [`DifferentialDrivetrain::follow_path`] to steer along a path.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub enum PathStrategy {
+    /// Steer towards a lookahead point on the path with adaptive pure pursuit (see [`PurePursuit`]).
+    #[default]
+    PurePursuit,
+
+    /// Steer based on heading and cross-track error relative to the nearest path segment
+    /// (see [`Stanley`]).
+    ///
+    /// The turn controller is used to apply the Stanley method's heading correction, and the
+    /// drivetrain's lookahead distance is used as the distance from the end of the path at which
+    /// the drivetrain moves to the final waypoint.
+    Stanley {
+        /// The cross-track error gain.
+        gain: f64,
+
+        /// The softening constant added to velocity.
+        softening: f64,
+    },
+}
+
+/// Where a [`PathMarker`] is placed along a path.
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub enum MarkerPosition {
+    /// A distance along the path, measured from the drivetrain's position when
+    /// [`DifferentialDrivetrain::follow_path`] was called.
+    Distance(f64),
+
+    /// The index of a waypoint in the path given to [`DifferentialDrivetrain::follow_path`].
+    ///
+    /// An index past the last waypoint places the marker at the end of the path.
+    Waypoint(usize),
+}
+
+/// What a [`PathMarker`] does when the drivetrain passes it.
+enum MarkerAction {
+    Callback(Box<dyn FnMut() + Send>),
+    Flag(Arc<AtomicBool>),
+}
+
+/// An event that fires once when the drivetrain passes a point on a path in [`DifferentialDrivetrain::follow_path`].
+///
+/// The drivetrain's position is projected onto the path to find how far along it the drivetrain is, so markers
+/// fire in order of their position along the path even if the drivetrain strays from it. Markers at the same
+/// position fire in the order they were given. Any markers that have not fired by the time the drivetrain
+/// settles at the end of the path fire then. Markers are discarded without firing if the motion times out or
+/// is replaced.
+///
+/// Callbacks run on the drivetrain's control task, so they should return quickly and must not call
+/// methods on the drivetrain.
+///
+/// # Example
+///
+/// ```
+/// let clamp = Arc::new(AtomicBool::new(false));
+///
+/// drivetrain.follow_path(
+///     path,
+///     vec![
+///         PathMarker::new(MarkerPosition::Distance(12.0), move || intake.start()),
+///         PathMarker::flag(MarkerPosition::Waypoint(3), Arc::clone(&clamp)),
+///     ],
+///     None,
+/// );
+/// ```
+pub struct PathMarker {
+    position: MarkerPosition,
+    action: MarkerAction,
+}
+
+impl PathMarker {
+    /// Construct a new [`PathMarker`] that calls a function when passed.
+    pub fn new(position: MarkerPosition, callback: impl FnMut() + Send + 'static) -> Self {
+        Self {
+            position,
+            action: MarkerAction::Callback(Box::new(callback)),
+        }
+    }
+
+    /// Construct a new [`PathMarker`] that sets a flag to `true` when passed.
+    pub fn flag(position: MarkerPosition, flag: Arc<AtomicBool>) -> Self {
+        Self {
+            position,
+            action: MarkerAction::Flag(flag),
+        }
+    }
+
+    /// Get where the marker is placed along the path.
+    pub fn position(&self) -> MarkerPosition {
+        self.position
+    }
+
+    /// Find the marker's distance along a path that has had the drivetrain's starting position inserted
+    /// before its first waypoint.
+    fn distance(&self, path: &PursuitPath) -> f64 {
+        match self.position {
+            MarkerPosition::Distance(distance) => distance,
+            MarkerPosition::Waypoint(index) => path
+                .distances()
+                .get(index + 1)
+                .copied()
+                .unwrap_or_else(|| path.length()),
+        }
+    }
+
+    fn fire(&mut self) {
+        match &mut self.action {
+            MarkerAction::Callback(callback) => callback(),
+            MarkerAction::Flag(flag) => flag.store(true, Ordering::Relaxed),
+        }
+    }
+}
+
+impl Debug for PathMarker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PathMarker")
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A path being followed by the drivetrain.
+#[derive(Debug, Clone, PartialEq)]
+enum PathFollower {
+    PurePursuit(PurePursuit),
+    Stanley(Stanley),
+
+    /// Pure pursuit without a velocity model, driving towards the lookahead point with the drive
+    /// and turn controllers.
+    ConstantSpeed(PurePursuit),
+}
+
+impl PathFollower {
+    fn path(&self) -> &PursuitPath {
+        match self {
+            PathFollower::PurePursuit(pursuit) | PathFollower::ConstantSpeed(pursuit) => {
+                pursuit.path()
+            }
+            PathFollower::Stanley(stanley) => stanley.path(),
+        }
+    }
+
+    fn is_finished(&self, position: Vec2) -> bool {
+        match self {
+            PathFollower::PurePursuit(pursuit) | PathFollower::ConstantSpeed(pursuit) => {
+                pursuit.is_finished(position)
+            }
+            PathFollower::Stanley(stanley) => stanley.is_finished(position),
+        }
+    }
+
+    fn progress(&self) -> PathProgress {
+        match self {
+            PathFollower::PurePursuit(pursuit) | PathFollower::ConstantSpeed(pursuit) => {
+                pursuit.progress()
+            }
+            PathFollower::Stanley(stanley) => stanley.progress(),
+        }
+    }
+}
+
+/// The direction that the drivetrain faces while moving towards a target.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub enum MotionDirection {
+    /// Drive with the front of the robot leading.
+    #[default]
+    Forwards,
+
+    /// Drive with the back of the robot leading.
+    Backwards,
+
+    /// Drive in whichever direction requires less turning to face the target when the motion starts.
+    Auto,
+}
+
+impl MotionDirection {
+    /// Choose between driving forwards or backwards to a point, given the robot's current pose.
+    ///
+    /// [`MotionDirection::Auto`] resolves to whichever direction faces the point with less turning.
+    fn resolve(&self, position: Vec2, heading: f64, point: Vec2) -> Self {
+        match self {
+            MotionDirection::Auto => {
+                if normalize_angle(heading - (point - position).angle()).abs() > FRAC_PI_2 {
+                    MotionDirection::Backwards
+                } else {
+                    MotionDirection::Forwards
+                }
+            }
+            direction => *direction,
+        }
+    }
+
+    /// The sign applied to drive power when moving in this direction.
+    ///
+    /// An unresolved [`MotionDirection::Auto`] is treated as forwards.
+    fn sign(&self) -> f64 {
+        match self {
+            MotionDirection::Forwards | MotionDirection::Auto => 1.0,
+            MotionDirection::Backwards => -1.0,
+        }
+    }
+
+    /// The angle between the robot's heading and the direction it is travelling in.
+    ///
+    /// An unresolved [`MotionDirection::Auto`] is treated as forwards.
+    fn heading_offset(&self) -> f64 {
+        match self {
+            MotionDirection::Forwards | MotionDirection::Auto => 0.0,
+            MotionDirection::Backwards => PI,
+        }
+    }
+}
+
+/// Options for chaining a motion into the next, set with [`MotionParameters::chain`].
+///
+/// A chained motion finishes once it is within `exit_distance` of its target, without slowing down to settle.
+/// Calling [`DifferentialDrivetrain::move_to_point`] or [`DifferentialDrivetrain::move_to_pose`] while a chained
+/// motion is running queues the new motion instead of replacing the running one, and the drivetrain moves on to
+/// it as soon as the running motion exits. This lets a sequence of motions flow into each other at speed.
+///
+/// # Example
+///
+/// ```
+/// let chained = Some(MotionParameters {
+///     chain: Some(ChainParameters::new(6.0, 40.0)),
+///     ..Default::default()
+/// });
+///
+/// drivetrain.move_to_point(Vec2::new(24.0, 0.0), chained);
+/// drivetrain.move_to_point(Vec2::new(24.0, 24.0), chained);
+/// drivetrain.move_to_point(Vec2::new(0.0, 24.0), None);
+/// drivetrain.wait_until_settled();
+/// ```
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct ChainParameters {
+    /// The distance from the target at which the motion exits.
+    pub exit_distance: f64,
+
+    /// The minimum drive power of the motion until it exits, so that it is still moving when
+    /// the next motion starts.
+    pub min_exit_speed: f64,
+}
+
+impl ChainParameters {
+    /// Construct a new set of [`ChainParameters`].
+    pub fn new(exit_distance: f64, min_exit_speed: f64) -> Self {
+        Self {
+            exit_distance,
+            min_exit_speed,
+        }
+    }
+}
+
+/// Options that tune an individual drivetrain motion.
+///
+/// Motions given `None` for their parameters use [`MotionParameters::default`], which allows the full range of
+/// motor power, drives forwards, and finishes according to the drivetrain's settle conditions.
+///
+/// # Example
+///
+/// ```
+/// drivetrain.move_to_point(
+///     Vec2::new(24.0, 24.0),
+///     Some(MotionParameters {
+///         max_speed: 60.0,
+///         timeout: Some(Duration::from_millis(1500)),
+///         ..Default::default()
+///     }),
+/// );
+/// ```
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub struct MotionParameters {
+    /// The maximum motor power of either side of the drivetrain, from `0.0` to `100.0`.
+    ///
+    /// Motor power is a percentage of the motors' full 12 volts. When either side exceeds this limit,
+    /// both sides are scaled down together to preserve the ratio between them, so the default of `100.0`
+    /// only keeps motions from requesting more voltage than the motors can supply.
+    pub max_speed: f64,
+
+    /// The minimum power of the motion's primary axis (driving, or turning for point turns) while its
+    /// controller output is nonzero. This is useful alongside `early_exit_error` to keep the drivetrain
+    /// moving through the end of a motion.
+    ///
+    /// For path following, this applies to the drivetrain's average forward power.
+    pub min_speed: f64,
+
+    /// The direction to face while moving towards a point or along a path.
+    ///
+    /// For [`DifferentialDrivetrain::turn_to_point`], this is the side of the robot that ends up facing
+    /// the point. Ignored by [`DifferentialDrivetrain::drive_distance`], where the sign of the distance sets
+    /// the direction, and by [`DifferentialDrivetrain::turn_to_angle`].
+    pub direction: MotionDirection,
+
+    /// How long the motion can run before it is considered settled, in place of the settle conditions' timeouts.
+    pub timeout: Option<Duration>,
+
+    /// An error at which the motion is considered settled without waiting for the settle conditions.
+    ///
+    /// This is a distance to the target for driving motions and an angle in radians for point turns.
+    pub early_exit_error: Option<f64>,
+
+    /// Chain this motion into the next motion rather than settling at its target (see [`ChainParameters`]).
+    ///
+    /// Only affects motions towards a point.
+    pub chain: Option<ChainParameters>,
+}
+
+impl Default for MotionParameters {
+    fn default() -> Self {
+        Self {
+            max_speed: 100.0,
+            min_speed: 0.0,
+            direction: MotionDirection::default(),
+            timeout: None,
+            early_exit_error: None,
+            chain: None,
+        }
+    }
+}
+
+impl MotionParameters {
+    /// Apply speed limits to the drive and turn power of a motion along an axis, returning left and right power.
+    fn limit_power(&self, axis: MotionAxis, drive_power: f64, turn_power: f64) -> (f64, f64) {
+        let raise = |power: f64, min_speed: f64| {
+            if power != 0.0 && power.abs() < min_speed {
+                min_speed * power.signum()
+            } else {
+                power
+            }
+        };
+
+        let (drive_power, turn_power) = match axis {
+            MotionAxis::Drive => {
+                let min_speed = match self.chain {
+                    Some(chain) => self.min_speed.max(chain.min_exit_speed),
+                    None => self.min_speed,
+                };
+
+                (raise(drive_power, min_speed), turn_power)
+            }
+            MotionAxis::Turn => (drive_power, raise(turn_power, self.min_speed)),
+        };
+
+        normalize_motor_power(
+            (drive_power + turn_power, drive_power - turn_power),
+            self.max_speed,
+        )
+    }
+}
+
 #[derive(Clone, PartialEq, Debug, Copy)]
 pub struct SettleCondition {
     pub error_tolerance: f64,
@@ -72,11 +558,23 @@ impl SettleCondition {
     }
 
     pub fn is_settled(&mut self, error: f64, output: f64) -> bool {
-        if error > self.error_tolerance || output > self.output_tolerance {
+        self.outcome(error, output, self.timeout).is_some()
+    }
+
+    /// Update the condition, returning [`MotionOutcome::Settled`] once the error and output have stayed within
+    /// tolerance for long enough, or [`MotionOutcome::TimedOut`] once `timeout` has passed without settling.
+    fn outcome(&mut self, error: f64, output: f64, timeout: Duration) -> Option<MotionOutcome> {
+        if error.abs() > self.error_tolerance || output.abs() > self.output_tolerance {
             self.timestamp = Instant::now();
         }
 
-        self.timestamp.elapsed() > self.duration || self.timeout_timestamp.elapsed() > self.timeout
+        if self.timestamp.elapsed() > self.duration {
+            Some(MotionOutcome::Settled)
+        } else if self.timeout_timestamp.elapsed() > timeout {
+            Some(MotionOutcome::TimedOut)
+        } else {
+            None
+        }
     }
 
     pub fn reset(&mut self) {
@@ -85,6 +583,62 @@ impl SettleCondition {
     }
 }
 
+/// How a drivetrain motion finished.
+#[derive(Clone, PartialEq, Eq, Debug, Copy)]
+pub enum MotionOutcome {
+    /// The motion reached its target, either by meeting its settle conditions, exiting early,
+    /// or exiting to the next motion in a chain.
+    Settled,
+
+    /// The motion ran out of time before reaching its target.
+    TimedOut,
+
+    /// The motion was stopped with [`DifferentialDrivetrain::cancel_motion`].
+    Cancelled,
+
+    /// The motion was replaced by a new target before it finished.
+    Interrupted,
+}
+
+impl MotionOutcome {
+    /// Combine the outcomes of the drive and turn axes of a motion that must settle on both.
+    fn combine(drive: Option<Self>, turn: Option<Self>) -> Option<Self> {
+        match (drive, turn) {
+            (Some(MotionOutcome::TimedOut), _) | (_, Some(MotionOutcome::TimedOut)) => {
+                Some(MotionOutcome::TimedOut)
+            }
+            (Some(drive), Some(_)) => Some(drive),
+            _ => None,
+        }
+    }
+}
+
+/// The result of a drivetrain motion, returned by [`DifferentialDrivetrain::wait_until_settled`].
+///
+/// # Example
+///
+/// ```
+/// drivetrain.move_to_point(Vec2::new(24.0, 24.0), None);
+///
+/// if drivetrain.wait_until_settled().outcome != MotionOutcome::Settled {
+///     // Skip this part of the routine.
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub struct MotionResult {
+    /// How the motion finished.
+    pub outcome: MotionOutcome,
+
+    /// The drive error when the motion finished.
+    pub drive_error: f64,
+
+    /// The turn error when the motion finished.
+    pub turn_error: f64,
+
+    /// How long the motion ran for.
+    pub elapsed: Duration,
+}
+
 #[derive(Default, Debug)]
 pub struct DifferentialDrivetrainState {
     drive_error: f64,
@@ -93,7 +647,169 @@ pub struct DifferentialDrivetrainState {
     turn_settle_condition: SettleCondition,
     target: DrivetrainTarget,
     lookahead_distance: f64,
-    settled: bool,
+    drive_profile: Option<ProfileConstraints>,
+    turn_profile: Option<ProfileConstraints>,
+    chassis_model: Option<ChassisModel>,
+    pursuit_constraints: Option<PursuitConstraints>,
+    path_strategy: PathStrategy,
+    path_follower: Option<PathFollower>,
+    path_markers: Vec<(f64, PathMarker)>,
+    motion_axis: MotionAxis,
+    motion_parameters: MotionParameters,
+    motion_start: Option<Instant>,
+    motion_queue: VecDeque<QueuedMotion>,
+    motion_id: u32,
+    result: Option<MotionResult>,
+    previous_result: Option<(u32, MotionResult)>,
+}
+
+/// A motion waiting for a chained motion to exit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QueuedMotion {
+    target: DrivetrainTarget,
+    axis: MotionAxis,
+    parameters: MotionParameters,
+}
+
+impl DifferentialDrivetrainState {
+    /// Reset settle conditions and start moving towards a new target.
+    fn start_motion(
+        &mut self,
+        target: DrivetrainTarget,
+        axis: MotionAxis,
+        parameters: MotionParameters,
+    ) {
+        // Reset settled state
+        self.drive_settle_condition.reset();
+        self.turn_settle_condition.reset();
+        self.result = None;
+
+        // Discard the previous path if we are no longer following it.
+        if !matches!(target, DrivetrainTarget::Path(_)) {
+            self.path_follower = None;
+            self.path_markers.clear();
+        }
+
+        // Set new target
+        self.target = target;
+        self.motion_axis = axis;
+        self.motion_parameters = parameters;
+        self.motion_start = Some(Instant::now());
+    }
+
+    /// Finish the current motion and start a new one, clearing any queued motions.
+    ///
+    /// If the current motion has not finished yet, it finishes with the `unfinished` outcome.
+    fn replace_motion(
+        &mut self,
+        unfinished: MotionOutcome,
+        target: DrivetrainTarget,
+        axis: MotionAxis,
+        parameters: MotionParameters,
+    ) {
+        self.finish(unfinished);
+        self.previous_result = self.result.map(|result| (self.motion_id, result));
+        self.motion_id = self.motion_id.wrapping_add(1);
+
+        self.motion_queue.clear();
+        self.start_motion(target, axis, parameters);
+    }
+
+    /// Finish the current motion with an outcome, unless it has already finished.
+    fn finish(&mut self, outcome: MotionOutcome) {
+        if self.result.is_none() {
+            self.result = Some(self.result_with(outcome));
+
+            // Markers only fire once the robot passes them or settles at the end of the path.
+            if outcome == MotionOutcome::TimedOut {
+                self.path_markers.clear();
+            }
+        }
+    }
+
+    /// The result of the current motion if it were to finish now with an outcome.
+    fn result_with(&self, outcome: MotionOutcome) -> MotionResult {
+        MotionResult {
+            outcome,
+            drive_error: self.drive_error,
+            turn_error: self.turn_error,
+            elapsed: self
+                .motion_start
+                .map_or(Duration::ZERO, |start| start.elapsed()),
+        }
+    }
+
+    /// Settle on a direction for a motion using [`MotionDirection::Auto`], now that it has started.
+    fn resolve_direction(&mut self, position: Vec2, heading: f64) {
+        let point = match self.target {
+            DrivetrainTarget::Point(point) | DrivetrainTarget::Pose(point, ..) => Some(point),
+            // Face the first waypoint after the robot's starting position.
+            DrivetrainTarget::Path(_) => self
+                .path_follower
+                .as_ref()
+                .and_then(|follower| follower.path().waypoints().get(1).copied()),
+            _ => None,
+        };
+
+        if let Some(point) = point {
+            self.motion_parameters.direction = self
+                .motion_parameters
+                .direction
+                .resolve(position, heading, point);
+        }
+    }
+
+    /// Returns `true` if a motion towards a new target should wait for the running motion to exit.
+    fn should_queue(&self, target: &DrivetrainTarget) -> bool {
+        let chained = match self.motion_queue.back() {
+            Some(motion) => motion.parameters.chain.is_some(),
+            None => self.motion_parameters.chain.is_some(),
+        };
+
+        chained
+            && self.result.is_none()
+            && matches!(target, DrivetrainTarget::Point(_) | DrivetrainTarget::Pose(..))
+    }
+
+    /// Check the drive settle condition. Its timeout is ignored if the current motion has its own.
+    fn drive_settled(&mut self, error: f64, output: f64) -> Option<MotionOutcome> {
+        let timeout = match self.motion_parameters.timeout {
+            Some(_) => Duration::MAX,
+            None => self.drive_settle_condition.timeout,
+        };
+
+        self.drive_settle_condition.outcome(error, output, timeout)
+    }
+
+    /// Check the turn settle condition. Its timeout is ignored if the current motion has its own.
+    fn turn_settled(&mut self, error: f64, output: f64) -> Option<MotionOutcome> {
+        let timeout = match self.motion_parameters.timeout {
+            Some(_) => Duration::MAX,
+            None => self.turn_settle_condition.timeout,
+        };
+
+        self.turn_settle_condition.outcome(error, output, timeout)
+    }
+
+    /// Returns `true` if the current motion has run for longer than its timeout.
+    fn timed_out(&self) -> bool {
+        match (self.motion_parameters.timeout, self.motion_start) {
+            (Some(timeout), Some(start)) => start.elapsed() > timeout,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the current motion's primary error is within its early exit error.
+    fn exited_early(&self) -> bool {
+        let error = match self.motion_axis {
+            MotionAxis::Drive => self.drive_error,
+            MotionAxis::Turn => self.turn_error,
+        };
+
+        self.motion_parameters
+            .early_exit_error
+            .map_or(false, |exit_error| error.abs() <= exit_error)
+    }
 }
 
 pub struct DifferentialDrivetrain<
@@ -160,33 +876,183 @@ impl<
 
                         let mut state = state.lock();
 
+                        // Once the end of a path is within the lookahead distance, settle on the final waypoint.
+                        if let DrivetrainTarget::Path(end) = state.target {
+                            if state
+                                .path_follower
+                                .as_ref()
+                                .map_or(true, |follower| follower.is_finished(position))
+                            {
+                                state.drive_settle_condition.reset();
+                                state.turn_settle_condition.reset();
+                                state.target = DrivetrainTarget::Point(end);
+                            }
+                        }
+
+                        state.resolve_direction(position, heading);
+
+                        let (axis, mut parameters) = (state.motion_axis, state.motion_parameters);
+                        let direction = parameters.direction;
+
+                        // Only enforce a minimum speed until the motion has finished, so that it can hold its target.
+                        if state.result.is_some() {
+                            parameters.min_speed = 0.0;
+                            parameters.chain = None;
+                        }
+
                         // Calculate left and right wheel power based on target type.
                         let (left_power, right_power) = match state.target {
                             DrivetrainTarget::Point(point) => {
                                 let displacement = point - position; // Displacement vector from our current position to the target
 
-                                state.turn_error = normalize_angle(heading - displacement.angle());
+                                state.turn_error = normalize_angle(
+                                    heading + direction.heading_offset() - displacement.angle(),
+                                );
                                 state.drive_error = displacement.length();
 
                                 let drive_power = drive_controller
                                     .lock()
                                     .update(state.drive_error, SAMPLE_RATE)
-                                    * state.turn_error.cos();
+                                    * state.turn_error.cos()
+                                    * direction.sign();
                                 let turn_power =
                                     turn_controller.lock().update(state.turn_error, SAMPLE_RATE);
 
                                 let drive_error = state.drive_error;
-                                if state
-                                    .drive_settle_condition
-                                    .is_settled(drive_error, drive_power)
+                                if let Some(outcome) = state.drive_settled(drive_error, drive_power) {
+                                    state.finish(outcome);
+                                }
+
+                                parameters.limit_power(axis, drive_power, turn_power)
+                            }
+
+                            DrivetrainTarget::Pose(point, target_heading, lead) => {
+                                let displacement = point - position;
+                                let distance = displacement.length();
+
+                                // Place a "carrot point" behind the target along the direction of travel, proportional
+                                // to the distance from the target. Driving towards the carrot curves the robot's path
+                                // so that it arrives at the target facing the target heading.
+                                let carrot = point
+                                    - Vec2::from_polar(
+                                        lead * distance,
+                                        target_heading + direction.heading_offset(),
+                                    );
+                                let final_turn_error = normalize_angle(heading - target_heading);
+
+                                state.drive_error = distance;
+                                state.turn_error =
+                                    if distance < state.drive_settle_condition.error_tolerance {
+                                        // The carrot's direction becomes unstable once the robot is on top of it,
+                                        // so face the target heading instead.
+                                        final_turn_error
+                                    } else {
+                                        normalize_angle(
+                                            heading + direction.heading_offset()
+                                                - (carrot - position).angle(),
+                                        )
+                                    };
+
+                                let drive_power = drive_controller
+                                    .lock()
+                                    .update(state.drive_error, SAMPLE_RATE)
+                                    * state.turn_error.cos()
+                                    * direction.sign();
+                                let turn_power =
+                                    turn_controller.lock().update(state.turn_error, SAMPLE_RATE);
+
+                                let drive_error = state.drive_error;
+                                let drive_outcome = state.drive_settled(drive_error, drive_power);
+                                let turn_outcome = state.turn_settled(final_turn_error, turn_power);
+
+                                if let Some(outcome) =
+                                    MotionOutcome::combine(drive_outcome, turn_outcome)
                                 {
-                                    state.settled = true;
+                                    state.finish(outcome);
                                 }
 
-                                normalize_motor_power(
-                                    (drive_power + turn_power, drive_power - turn_power),
-                                    12000.0,
-                                )
+                                parameters.limit_power(axis, drive_power, turn_power)
+                            }
+
+                            DrivetrainTarget::Path(_) => {
+                                let model = state.chassis_model.unwrap_or_default();
+
+                                // When driving backwards, follow the path as if the back of the robot were the front.
+                                let facing = heading + direction.heading_offset();
+
+                                let follower = state.path_follower.as_mut().unwrap();
+                                let (left_power, right_power) = match follower {
+                                    PathFollower::PurePursuit(pursuit) => {
+                                        let (linear_velocity, angular_velocity) =
+                                            pursuit.update(position, facing, SAMPLE_RATE);
+                                        let (progress, lookahead_point) =
+                                            (pursuit.progress(), pursuit.lookahead_point());
+
+                                        state.drive_error = progress.distance_remaining;
+                                        state.turn_error = normalize_angle(
+                                            facing - (lookahead_point - position).angle(),
+                                        );
+
+                                        let (left_power, right_power) = model.wheel_powers(
+                                            linear_velocity * direction.sign(),
+                                            angular_velocity,
+                                        );
+
+                                        parameters.limit_power(
+                                            axis,
+                                            (left_power + right_power) / 2.0,
+                                            (left_power - right_power) / 2.0,
+                                        )
+                                    }
+                                    PathFollower::Stanley(stanley) => {
+                                        let (linear_velocity, heading_correction) =
+                                            stanley.update(position, facing, SAMPLE_RATE);
+
+                                        state.drive_error = stanley.progress().distance_remaining;
+                                        state.turn_error = -heading_correction;
+
+                                        let (drive_power, _) = model
+                                            .wheel_powers(linear_velocity * direction.sign(), 0.0);
+                                        let turn_power = turn_controller
+                                            .lock()
+                                            .update(state.turn_error, SAMPLE_RATE);
+
+                                        parameters.limit_power(axis, drive_power, turn_power)
+                                    }
+                                    PathFollower::ConstantSpeed(pursuit) => {
+                                        pursuit.update(position, facing, SAMPLE_RATE);
+                                        let remaining = pursuit.progress().distance_remaining;
+                                        let displacement = pursuit.lookahead_point() - position;
+
+                                        state.drive_error = remaining;
+                                        state.turn_error =
+                                            normalize_angle(facing - displacement.angle());
+
+                                        // The lookahead point stays roughly the lookahead distance away, so driving
+                                        // towards it moves at a roughly constant speed.
+                                        let drive_power = drive_controller
+                                            .lock()
+                                            .update(displacement.length(), SAMPLE_RATE)
+                                            * state.turn_error.cos()
+                                            * direction.sign();
+                                        let turn_power = turn_controller
+                                            .lock()
+                                            .update(state.turn_error, SAMPLE_RATE);
+
+                                        parameters.limit_power(axis, drive_power, turn_power)
+                                    }
+                                };
+
+                                // The path only settles once the robot has moved on to its final waypoint, but it
+                                // can still time out while following the path.
+                                let drive_error = state.drive_error;
+                                let drive_output = (left_power + right_power) / 2.0;
+                                let outcome = state.drive_settled(drive_error, drive_output);
+                                if outcome == Some(MotionOutcome::TimedOut) {
+                                    state.finish(MotionOutcome::TimedOut);
+                                }
+
+                                (left_power, right_power)
                             }
 
                             DrivetrainTarget::DistanceAndHeading(
@@ -204,31 +1070,155 @@ impl<
 
                                 let (drive_error, turn_error) =
                                     (state.drive_error, state.turn_error);
-                                if state
-                                    .drive_settle_condition
-                                    .is_settled(drive_error, drive_power)
-                                    && state
-                                        .turn_settle_condition
-                                        .is_settled(turn_error, turn_power)
+                                let drive_outcome = state.drive_settled(drive_error, drive_power);
+                                let turn_outcome = state.turn_settled(turn_error, turn_power);
+
+                                if let Some(outcome) =
+                                    MotionOutcome::combine(drive_outcome, turn_outcome)
                                 {
-                                    state.settled = true;
+                                    state.finish(outcome);
                                 }
 
-                                normalize_motor_power(
-                                    (drive_power + turn_power, drive_power - turn_power),
-                                    12000.0,
-                                )
+                                parameters.limit_power(axis, drive_power, turn_power)
+                            }
+
+                            DrivetrainTarget::ProfiledDistanceAndHeading {
+                                start_distance,
+                                start_heading,
+                                axis,
+                                profile,
+                                feedforward,
+                                start_time,
+                            } => {
+                                let (target_distance, target_heading) =
+                                    state.target.distance_and_heading().unwrap();
+
+                                state.drive_error = target_distance - forward_travel;
+                                state.turn_error = normalize_angle(heading - target_heading);
+
+                                // Sample the profile to find where the setpoint should be right now.
+                                let elapsed = start_time.elapsed();
+                                let setpoint = profile.sample(elapsed);
+                                let feedforward_power = feedforward.calculate(
+                                    setpoint.position,
+                                    setpoint.velocity,
+                                    setpoint.acceleration,
+                                );
+
+                                // The profiled axis tracks the moving setpoint, while the other holds its target.
+                                let (drive_power, turn_power) = match axis {
+                                    MotionAxis::Drive => (
+                                        drive_controller.lock().update(
+                                            start_distance + setpoint.position - forward_travel,
+                                            SAMPLE_RATE,
+                                        ) + feedforward_power,
+                                        turn_controller
+                                            .lock()
+                                            .update(state.turn_error, SAMPLE_RATE),
+                                    ),
+                                    MotionAxis::Turn => (
+                                        drive_controller
+                                            .lock()
+                                            .update(state.drive_error, SAMPLE_RATE),
+                                        // Positive turn power decreases heading, so feedforward is subtracted.
+                                        turn_controller.lock().update(
+                                            normalize_angle(
+                                                heading - (start_heading + setpoint.position),
+                                            ),
+                                            SAMPLE_RATE,
+                                        ) - feedforward_power,
+                                    ),
+                                };
+
+                                let (drive_error, turn_error) =
+                                    (state.drive_error, state.turn_error);
+                                let drive_outcome = state.drive_settled(drive_error, drive_power);
+                                let turn_outcome = state.turn_settled(turn_error, turn_power);
+
+                                // The motion can only settle once the profile has finished, but can time out at any point.
+                                match MotionOutcome::combine(drive_outcome, turn_outcome) {
+                                    Some(MotionOutcome::Settled) if elapsed < profile.duration() => {}
+                                    Some(outcome) => state.finish(outcome),
+                                    None => {}
+                                }
+
+                                parameters.limit_power(axis, drive_power, turn_power)
                             }
 
                             DrivetrainTarget::MotorPower(left_power, right_power) => {
                                 state.drive_error = 0.0;
                                 state.turn_error = 0.0;
-                                state.settled = true;
+                                state.finish(MotionOutcome::Settled);
 
                                 (left_power, right_power)
                             }
+
+                            DrivetrainTarget::ChassisVelocity(linear_velocity, angular_velocity) => {
+                                state.drive_error = 0.0;
+                                state.turn_error = 0.0;
+                                state.finish(MotionOutcome::Settled);
+
+                                match state.chassis_model {
+                                    Some(model) => {
+                                        model.wheel_powers(linear_velocity, angular_velocity)
+                                    }
+                                    None => (0.0, 0.0),
+                                }
+                            }
                         };
 
+                        // Finish the motion early if it is close enough to its target or has timed out.
+                        if state.exited_early() {
+                            state.finish(MotionOutcome::Settled);
+                        } else if state.timed_out() {
+                            state.finish(MotionOutcome::TimedOut);
+                        }
+
+                        // Chained motions exit without settling once they are close enough to their target.
+                        if let Some(chain) = parameters.chain {
+                            if matches!(
+                                state.target,
+                                DrivetrainTarget::Point(_) | DrivetrainTarget::Pose(..)
+                            ) && state.drive_error.abs() <= chain.exit_distance
+                            {
+                                state.finish(MotionOutcome::Settled);
+                            }
+                        }
+
+                        // Fire path markers that the robot has passed. Reaching the end of the path passes every marker.
+                        if !state.path_markers.is_empty() {
+                            let state = &mut *state;
+
+                            if let Some(follower) = &state.path_follower {
+                                let travelled = match state.result {
+                                    Some(result) if result.outcome == MotionOutcome::Settled => f64::MAX,
+                                    _ => {
+                                        let (path, progress) = (follower.path(), follower.progress());
+                                        let closest =
+                                            path.closest_index(position, progress.segment_index);
+
+                                        path.project(position, closest).1
+                                    }
+                                };
+
+                                state.path_markers.retain_mut(|(distance, marker)| {
+                                    if *distance <= travelled {
+                                        marker.fire();
+                                        false
+                                    } else {
+                                        true
+                                    }
+                                });
+                            }
+                        }
+
+                        // Hand over to the next queued motion without waiting for the robot to stop.
+                        if state.result.is_some() {
+                            if let Some(motion) = state.motion_queue.pop_front() {
+                                state.start_motion(motion.target, motion.axis, motion.parameters);
+                            }
+                        }
+
                         // Set the motor voltages
                         for motor in motors.0.lock().iter_mut() {
                             motor
@@ -257,130 +1247,240 @@ impl<
     /// - Resets drivetrain settle condition timers, since they now have a new target to get to.
     /// - Obtains a lock on the drivetrain state and sets it to a new value.
     fn set_target(&mut self, target: DrivetrainTarget) {
-        let mut state = self.state.lock();
+        self.set_motion(target, MotionAxis::Drive, MotionParameters::default());
+    }
 
-        // Reset settled state
-        state.drive_settle_condition.reset();
-        state.turn_settle_condition.reset();
-        state.settled = false;
+    /// Same as [`DifferentialDrivetrain::set_target`], but for a motion along an axis with parameters.
+    fn set_motion(
+        &mut self,
+        target: DrivetrainTarget,
+        axis: MotionAxis,
+        parameters: MotionParameters,
+    ) {
+        let mut state = self.state.lock();
 
-        // Set new target
-        state.target = target;
+        if state.should_queue(&target) {
+            state.motion_queue.push_back(QueuedMotion {
+                target,
+                axis,
+                parameters,
+            });
+        } else {
+            state.replace_motion(MotionOutcome::Interrupted, target, axis, parameters);
+        }
     }
 
     /// Moves the drivetrain in a straight line for a certain distance.
-    pub fn drive_distance(&mut self, distance: f64) {
-        let target = self.state.lock().target;
+    ///
+    /// If drive profile constraints are set, the drivetrain will follow a motion profile
+    /// over the distance. Passing `None` for `parameters` uses [`MotionParameters::default`].
+    pub fn drive_distance(&mut self, distance: f64, parameters: Option<MotionParameters>) {
+        let (target, drive_profile) = {
+            let state = self.state.lock();
+            (state.target, state.drive_profile)
+        };
         let (forward_travel, heading) = {
             let tracking = self.tracking.lock();
             (tracking.forward_travel(), tracking.heading())
         };
 
+        // Keep the previous target heading if there was one.
+        let heading = match target.distance_and_heading() {
+            Some((_, heading)) => heading,
+            None => heading,
+        };
+
         // Add `distance` to the drivetrain's target distance.
-        self.set_target(DrivetrainTarget::DistanceAndHeading(
-            forward_travel + distance,
-            match target {
-                DrivetrainTarget::DistanceAndHeading(_, heading) => heading,
-                _ => heading,
+        let target = match drive_profile {
+            Some(constraints) => DrivetrainTarget::ProfiledDistanceAndHeading {
+                start_distance: forward_travel,
+                start_heading: heading,
+                axis: MotionAxis::Drive,
+                profile: constraints.profile(distance),
+                feedforward: constraints.feedforward,
+                start_time: Instant::now(),
             },
-        ));
+            None => DrivetrainTarget::DistanceAndHeading(forward_travel + distance, heading),
+        };
+
+        self.set_motion(target, MotionAxis::Drive, parameters.unwrap_or_default());
     }
 
     /// Turns the drivetrain in place to face a certain angle.
-    pub fn turn_to_angle(&mut self, angle: f64) {
-        let target = self.state.lock().target;
-        let forward_travel = self.tracking.lock().forward_travel();
+    ///
+    /// If turn profile constraints are set, the drivetrain will follow a motion profile
+    /// through the shortest turn to the angle. Passing `None` for `parameters` uses
+    /// [`MotionParameters::default`].
+    pub fn turn_to_angle(&mut self, angle: f64, parameters: Option<MotionParameters>) {
+        let (target, turn_profile) = {
+            let state = self.state.lock();
+            (state.target, state.turn_profile)
+        };
+        let (forward_travel, heading) = {
+            let tracking = self.tracking.lock();
+            (tracking.forward_travel(), tracking.heading())
+        };
 
-        // Set target heading, keeping the current forward position.
-        self.set_target(DrivetrainTarget::DistanceAndHeading(
-            match target {
-                DrivetrainTarget::DistanceAndHeading(distance, _) => distance,
-                _ => forward_travel,
+        // Keep the previous target distance if there was one, otherwise keep the current forward position.
+        let distance = match target.distance_and_heading() {
+            Some((distance, _)) => distance,
+            None => forward_travel,
+        };
+
+        // Set target heading.
+        let target = match turn_profile {
+            Some(constraints) => DrivetrainTarget::ProfiledDistanceAndHeading {
+                start_distance: distance,
+                start_heading: heading,
+                axis: MotionAxis::Turn,
+                profile: constraints.profile(normalize_angle(angle - heading)),
+                feedforward: constraints.feedforward,
+                start_time: Instant::now(),
             },
-            angle,
-        ));
+            None => DrivetrainTarget::DistanceAndHeading(distance, angle),
+        };
+
+        self.set_motion(target, MotionAxis::Turn, parameters.unwrap_or_default());
     }
 
     /// Turns the drivetrain in place to face the direction of a certain point.
-    pub fn turn_to_point(&mut self, point: Vec2) {
-        let target = self.state.lock().target;
-        let (position, forward_travel) = {
+    ///
+    /// If the direction in `parameters` is [`MotionDirection::Backwards`], the back of the drivetrain
+    /// will face the point instead. [`MotionDirection::Auto`] faces whichever side of the drivetrain
+    /// is closer to the point.
+    pub fn turn_to_point(&mut self, point: Vec2, parameters: Option<MotionParameters>) {
+        let (position, heading) = {
             let tracking = self.tracking.lock();
-            (tracking.position(), tracking.forward_travel())
+            (tracking.position(), tracking.heading())
         };
-
-        let displacement = point - position;
-
-        // Set target heading, keeping the current forward position.
-        self.set_target(DrivetrainTarget::DistanceAndHeading(
-            match target {
-                DrivetrainTarget::DistanceAndHeading(distance, _) => distance,
-                _ => forward_travel,
-            },
-            displacement.angle(),
-        ));
+        let direction = parameters
+            .unwrap_or_default()
+            .direction
+            .resolve(position, heading, point);
+
+        self.turn_to_angle(
+            (point - position).angle() + direction.heading_offset(),
+            parameters,
+        );
     }
 
     /// Moves the drivetrain to a certain point by turning and driving at the same time.
-    pub fn move_to_point(&mut self, point: impl Into<Vec2>) {
-        self.set_target(DrivetrainTarget::Point(point.into()));
+    ///
+    /// The direction in `parameters` chooses whether the drivetrain drives forwards or backwards to the point,
+    /// such as to lead with a rear-mounted mechanism. Passing `None` for `parameters` uses
+    /// [`MotionParameters::default`].
+    pub fn move_to_point(&mut self, point: impl Into<Vec2>, parameters: Option<MotionParameters>) {
+        self.set_motion(
+            DrivetrainTarget::Point(point.into()),
+            MotionAxis::Drive,
+            parameters.unwrap_or_default(),
+        );
     }
 
-    /// Moves the drivetrain along a path defined by a series of waypoints.
-    pub fn follow_path(&mut self, mut path: Vec<Vec2>) {
-        let lookahead_distance = self.state.lock().lookahead_distance;
+    /// Moves the drivetrain to a certain point, arriving facing a certain heading.
+    ///
+    /// The drivetrain drives towards a "carrot point" placed behind the target point along the target
+    /// heading, curving its path to line up with the heading as it approaches. `lead` is the distance of
+    /// the carrot behind the target as a fraction of the distance to the target. Higher values produce
+    /// wider curves. Values between `0.0` and `1.0` are typical. A lead of `0.0` drives straight to the
+    /// point like [`DifferentialDrivetrain::move_to_point`], then turns to face the heading.
+    ///
+    /// Passing `None` for `parameters` uses [`MotionParameters::default`].
+    pub fn move_to_pose(
+        &mut self,
+        point: impl Into<Vec2>,
+        heading: f64,
+        lead: f64,
+        parameters: Option<MotionParameters>,
+    ) {
+        self.set_motion(
+            DrivetrainTarget::Pose(point.into(), heading, lead),
+            MotionAxis::Drive,
+            parameters.unwrap_or_default(),
+        );
+    }
 
-        let mut path_loop = Loop::new(Duration::from_millis(10));
+    /// Moves the drivetrain along a path defined by a series of waypoints.
+    ///
+    /// By default, the drivetrain uses adaptive pure pursuit, steering towards a lookahead point on the path,
+    /// slowing down for tight curves and growing its lookahead distance with speed according to its pursuit
+    /// constraints. A different steering method can be chosen with [`DifferentialDrivetrain::set_path_strategy`].
+    /// Once the end of the path is within the lookahead distance, the drivetrain moves to the final waypoint
+    /// and settles there. Progress along the path can be checked with [`DifferentialDrivetrain::path_progress`],
+    /// and `markers` can be used to trigger events as the drivetrain passes points on the path (see [`PathMarker`]).
+    ///
+    /// Adaptive pure pursuit and Stanley need both pursuit constraints (see
+    /// [`DifferentialDrivetrain::set_pursuit_constraints`]) and a chassis model (see
+    /// [`DifferentialDrivetrain::set_chassis_model`]) to plan and produce velocities. If either is missing, the path
+    /// strategy is ignored and the drivetrain instead drives towards a lookahead point at a fixed lookahead distance
+    /// using its drive and turn controllers, moving at a roughly constant speed.
+    ///
+    /// Passing `None` for `parameters` uses [`MotionParameters::default`]. The maximum speed limits motor power
+    /// on top of the pursuit constraints' velocity limits, and the direction chooses whether the path is followed
+    /// forwards or backwards.
+    pub fn follow_path(
+        &mut self,
+        mut path: Vec<Vec2>,
+        markers: Vec<PathMarker>,
+        parameters: Option<MotionParameters>,
+    ) {
+        let (lookahead_distance, constraints, strategy, has_chassis_model) = {
+            let state = self.state.lock();
+            (
+                state.lookahead_distance,
+                state.pursuit_constraints,
+                state.path_strategy,
+                state.chassis_model.is_some(),
+            )
+        };
 
         // Ensure that there is an initial intersection by inserting the current position at the start of the path.
         // This effectively creates a big starting line segment between the robot's current location and the first waypoint,
         // meaning the robot will target the first waypoint in the path even if the lookahead circle is far from it.
-        path.insert(0, self.tracking.lock().position());
-
-        // Loop through each waypoint.
-        for (i, waypoint) in path.iter().enumerate() {
-            // Find the next waypoint after the current one. This will form our line segment.
-            // If there isn't a next waypoint, then we are targeting the final point and can stop.
-            let next_waypoint = if let Some(waypoint) = path.get(i + 1) {
-                waypoint
-            } else {
-                continue;
-            };
-
-            // Move to the intersection point between the lookahead circle and the line segment formed between
-            // the current and next waypoint until the lookahead circle encompasses the next waypoint.
-            while self.tracking.lock().position().distance(*next_waypoint) > lookahead_distance {
-                // Find intersections using the line segment + circle intersection formula.
-                let intersections = LineCircleIntersections::compute_bounded(
-                    (*waypoint, *next_waypoint), // Line segment from waypoint to next_waypoint
-                    (self.tracking.lock().position(), lookahead_distance), // Lookahead circle
-                );
-
-                self.set_target(DrivetrainTarget::Point(match intersections {
-                    // The line segment has secant intersections with the lookahead circle, resulting in two points of intersection.
-                    // Choose the one cloeset to the next waypoint to ensure we go forward and not backwards on the path.
-                    LineCircleIntersections::Secant(point_1, point_2) => {
-                        if point_1.distance(*next_waypoint) < point_2.distance(*next_waypoint) {
-                            point_1
-                        } else {
-                            point_2
-                        }
-                    }
-
-                    // There is one intersection (a tangent line). Move to it.
-                    LineCircleIntersections::Tangent(point) => point,
+        let position = self.tracking.lock().position();
+        path.insert(0, position);
 
-                    // The lookahead circle does not intersect the waypoint line segment at all.
-                    // Presumably, the robot has been greatly knocked off course, so we'll keep the last known intersection
-                    // as our target to allow the drivetrain to get back on the path.
-                    LineCircleIntersections::None => continue,
-                }));
+        let end = *path.last().unwrap();
 
-                path_loop.delay();
+        // Without a velocity model, the path can only be followed at a constant speed.
+        let follower = match (strategy, constraints.filter(|_| has_chassis_model)) {
+            (PathStrategy::PurePursuit, Some(constraints)) => {
+                PathFollower::PurePursuit(PurePursuit::new(path, lookahead_distance, constraints))
             }
-        }
+            (PathStrategy::Stanley { gain, softening }, Some(constraints)) => {
+                PathFollower::Stanley(Stanley::new(
+                    path,
+                    gain,
+                    softening,
+                    lookahead_distance,
+                    constraints,
+                ))
+            }
+            (_, None) => PathFollower::ConstantSpeed(PurePursuit::new(
+                path,
+                lookahead_distance,
+                PursuitConstraints::default(),
+            )),
+        };
+        let mut markers: Vec<_> = markers
+            .into_iter()
+            .map(|marker| (marker.distance(follower.path()), marker))
+            .collect();
+
+        // Markers passed in the same update fire in the order they are stored.
+        markers.sort_by(|(a, _), (b, _)| a.total_cmp(b));
 
-        self.wait_until_settled();
+        // The follower must be installed alongside the target, otherwise the control task could run
+        // the new follower against the previous motion (or the previous follower against the new target).
+        let mut state = self.state.lock();
+        state.replace_motion(
+            MotionOutcome::Interrupted,
+            DrivetrainTarget::Path(end),
+            MotionAxis::Drive,
+            parameters.unwrap_or_default(),
+        );
+        state.path_follower = Some(follower);
+        state.path_markers = markers;
     }
 
     // Holds the current angle and position of the drivetrain.
@@ -407,22 +1507,160 @@ impl<
         ));
     }
 
-    pub fn wait_until_settled(&self) {
+    /// Drives the chassis at a linear velocity and counterclockwise angular velocity in radians,
+    /// such as the output of a [`RamseteController`](crate::ramsete::RamseteController).
+    ///
+    /// Velocities are converted into motor power with the drivetrain's chassis model (see
+    /// [`DifferentialDrivetrain::set_chassis_model`]). Without one, the drivetrain stops instead.
+    pub fn control_velocity(&mut self, linear_velocity: f64, angular_velocity: f64) {
+        self.set_target(DrivetrainTarget::ChassisVelocity(
+            linear_velocity,
+            angular_velocity,
+        ));
+    }
+
+    /// Stops the current motion and any queued motions, cutting power to the motors.
+    ///
+    /// If the motion has not finished yet, it finishes with [`MotionOutcome::Cancelled`].
+    pub fn cancel_motion(&mut self) {
+        self.state.lock().replace_motion(
+            MotionOutcome::Cancelled,
+            DrivetrainTarget::MotorPower(0.0, 0.0),
+            MotionAxis::Drive,
+            MotionParameters::default(),
+        );
+    }
+
+    /// Waits for the current motion to finish, returning how it finished.
+    ///
+    /// If the motion is chained, this waits for every motion queued after it. If the motion is replaced by
+    /// a new target before it finishes, this returns early with [`MotionOutcome::Interrupted`] (or
+    /// [`MotionOutcome::Cancelled`] if it was replaced by [`DifferentialDrivetrain::cancel_motion`]).
+    pub fn wait_until_settled(&self) -> MotionResult {
         let mut spinlock = Loop::new(Duration::from_millis(10));
+        let motion_id = self.state.lock().motion_id;
+
+        loop {
+            {
+                let state = self.state.lock();
+
+                if state.motion_id != motion_id {
+                    return match state.previous_result {
+                        Some((id, result)) if id == motion_id => result,
+                        // More than one motion has started since, so the original result is gone.
+                        _ => state.result_with(MotionOutcome::Interrupted),
+                    };
+                }
+
+                if let Some(result) = state.result {
+                    return result;
+                }
+            }
 
-        while !self.state.lock().settled {
             spinlock.delay();
         }
     }
 
-    pub fn wait_until(&self, condition: bool) {
+    /// Waits until a condition on the drivetrain is met, checking it every 10 milliseconds.
+    ///
+    /// Returns `true` once the condition is met, or `false` if `timeout` passes first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// drivetrain.follow_path(path, Vec::new(), None);
+    ///
+    /// // Start the intake once the drivetrain is facing left.
+    /// drivetrain.wait_until(|drivetrain| drivetrain.heading() > 90.0.to_radians(), None);
+    /// intake.start();
+    /// ```
+    pub fn wait_until(
+        &self,
+        mut condition: impl FnMut(&Self) -> bool,
+        timeout: Option<Duration>,
+    ) -> bool {
         let mut spinlock = Loop::new(Duration::from_millis(10));
+        let start = Instant::now();
+
+        loop {
+            if condition(self) {
+                return true;
+            }
+
+            if timeout.map_or(false, |timeout| start.elapsed() > timeout) {
+                return false;
+            }
 
-        while !condition {
             spinlock.delay();
         }
     }
 
+    /// Waits until the drivetrain is within `distance` of a point.
+    ///
+    /// Returns `false` if `timeout` passes first.
+    pub fn wait_until_near(
+        &self,
+        point: impl Into<Vec2>,
+        distance: f64,
+        timeout: Option<Duration>,
+    ) -> bool {
+        let point = point.into();
+
+        self.wait_until(
+            |drivetrain| drivetrain.position().distance(point) <= distance,
+            timeout,
+        )
+    }
+
+    /// Waits until the drivetrain has travelled `distance` forwards or backwards since this was called.
+    ///
+    /// Returns `false` if `timeout` passes first.
+    pub fn wait_until_travelled(&self, distance: f64, timeout: Option<Duration>) -> bool {
+        let start = self.forward_travel();
+
+        self.wait_until(
+            |drivetrain| (drivetrain.forward_travel() - start).abs() >= distance.abs(),
+            timeout,
+        )
+    }
+
+    /// Waits until the drivetrain's heading crosses an angle in either direction, or is already at it.
+    ///
+    /// Returns `false` if `timeout` passes first.
+    pub fn wait_until_heading_crosses(&self, angle: f64, timeout: Option<Duration>) -> bool {
+        let mut previous_error = normalize_angle(self.heading() - angle);
+
+        self.wait_until(
+            |drivetrain| {
+                let error = normalize_angle(drivetrain.heading() - angle);
+
+                // The error changes sign when crossing the angle, but also when crossing the opposite angle,
+                // where it jumps between -π and π.
+                let crossed = error == 0.0
+                    || (error.signum() != previous_error.signum()
+                        && (error - previous_error).abs() < PI);
+                previous_error = error;
+
+                crossed
+            },
+            timeout,
+        )
+    }
+
+    /// Waits until the drivetrain has travelled `distance` along the path it is following.
+    ///
+    /// Returns `false` if `timeout` passes first. See [`DifferentialDrivetrain::path_progress`].
+    pub fn wait_until_path_progress(&self, distance: f64, timeout: Option<Duration>) -> bool {
+        self.wait_until(
+            |drivetrain| {
+                drivetrain
+                    .path_progress()
+                    .map_or(false, |progress| progress.distance_travelled >= distance)
+            },
+            timeout,
+        )
+    }
+
     pub fn position(&self) -> Vec2 {
         self.tracking.lock().position()
     }
@@ -447,18 +1685,92 @@ impl<
         self.state.lock().lookahead_distance
     }
 
+    /// Get the model used to convert chassis velocities into motor power.
+    pub fn chassis_model(&self) -> Option<ChassisModel> {
+        self.state.lock().chassis_model
+    }
+
+    /// Sets the model used to convert chassis velocities into motor power.
+    pub fn set_chassis_model(&mut self, model: Option<ChassisModel>) {
+        self.state.lock().chassis_model = model;
+    }
+
+    /// Get the constraints used for adaptive pure pursuit in [`DifferentialDrivetrain::follow_path`].
+    pub fn pursuit_constraints(&self) -> Option<PursuitConstraints> {
+        self.state.lock().pursuit_constraints
+    }
+
+    /// Sets the constraints used for adaptive pure pursuit in [`DifferentialDrivetrain::follow_path`].
+    /// Passing `None` makes paths be followed at a constant speed instead.
+    pub fn set_pursuit_constraints(&mut self, constraints: Option<PursuitConstraints>) {
+        self.state.lock().pursuit_constraints = constraints;
+    }
+
+    /// Get the method used to steer along paths in [`DifferentialDrivetrain::follow_path`].
+    pub fn path_strategy(&self) -> PathStrategy {
+        self.state.lock().path_strategy
+    }
+
+    /// Sets the method used to steer along paths in [`DifferentialDrivetrain::follow_path`].
+    pub fn set_path_strategy(&mut self, strategy: PathStrategy) {
+        self.state.lock().path_strategy = strategy;
+    }
+
+    /// Get the constraints used to profile straight drives, if profiling is enabled.
+    pub fn drive_profile(&self) -> Option<ProfileConstraints> {
+        self.state.lock().drive_profile
+    }
+
+    /// Sets the constraints used to profile straight drives. Passing `None` disables profiling,
+    /// causing the drive setpoint to jump straight to the target.
+    pub fn set_drive_profile(&mut self, constraints: Option<ProfileConstraints>) {
+        self.state.lock().drive_profile = constraints;
+    }
+
+    /// Get the constraints used to profile point turns, if profiling is enabled.
+    pub fn turn_profile(&self) -> Option<ProfileConstraints> {
+        self.state.lock().turn_profile
+    }
+
+    /// Sets the constraints used to profile point turns. Passing `None` disables profiling,
+    /// causing the turn setpoint to jump straight to the target.
+    pub fn set_turn_profile(&mut self, constraints: Option<ProfileConstraints>) {
+        self.state.lock().turn_profile = constraints;
+    }
+
+    /// Get how far along its path the drivetrain is, if it is following or has just followed a path.
+    ///
+    /// Segment `0` runs from the drivetrain's position when [`DifferentialDrivetrain::follow_path`] was called
+    /// to the first waypoint, so the segment index is also the index of the waypoint being driven towards.
+    pub fn path_progress(&self) -> Option<PathProgress> {
+        self.state
+            .lock()
+            .path_follower
+            .as_ref()
+            .map(|follower| follower.progress())
+    }
+
     pub fn is_settled(&self) -> bool {
-        self.state.lock().settled
+        self.state.lock().result.is_some()
+    }
+
+    /// Get the result of the current motion, or `None` if it has not finished yet.
+    pub fn motion_result(&self) -> Option<MotionResult> {
+        self.state.lock().result
     }
 
     pub fn tracking(&self) -> Arc<Mutex<T>> {
         Arc::clone(&self.tracking)
     }
 
+    /// Get a shared reference to the drive controller, such as for reading its diagnostics
+    /// (see [`PIDController::diagnostics`](crate::controller::PIDController::diagnostics)).
     pub fn drive_controller(&self) -> Arc<Mutex<U>> {
         Arc::clone(&self.drive_controller)
     }
 
+    /// Get a shared reference to the turn controller, such as for reading its diagnostics
+    /// (see [`PIDController::diagnostics`](crate::controller::PIDController::diagnostics)).
     pub fn turn_controller(&self) -> Arc<Mutex<V>> {
         Arc::clone(&self.turn_controller)
     }
@@ -3,13 +3,20 @@
 
 extern crate alloc;
 
+pub mod autotune;
+pub mod chassis;
 pub mod controller;
 pub mod drivetrain;
+pub mod lqr;
 pub mod math;
+pub mod motion_profile;
+pub mod mpc;
+pub mod ramsete;
 pub mod devices;
 pub mod tracking;
+pub mod trajectory;
 pub mod timer;
 
 pub mod prelude {
-    pub use crate::{controller::*, drivetrain::*, math::Vec2, devices::*, tracking::*};
+    pub use crate::{autotune::*, chassis::*, controller::*, drivetrain::*, lqr::*, math::Vec2, motion_profile::*, mpc::*, ramsete::*, devices::*, tracking::*, trajectory::*};
 }
\ No newline at end of file
@@ -0,0 +1,440 @@
+use core::time::Duration;
+use num_traits::real::Real;
+
+use crate::{
+    controller::FeedbackController,
+    math::{normalize_angle, Vec2},
+    trajectory::TrajectoryPoint,
+};
+
+/// Solve the discrete-time algebraic Riccati equation by iteration, returning the optimal
+/// linear-quadratic regulator (LQR) gain matrix `K` for a system `x[k + 1] = A x[k] + B u[k]`.
+///
+/// The gain minimizes the sum of `xᵀ Q x + uᵀ R u` over time, where `Q` and `R` are diagonal matrices
+/// given by `state_weights` and `input_weights`. The optimal input for a state is `u = -K x`.
+///
+/// Iteration stops once no gain changes by more than `tolerance` or after `max_iterations`. Since this
+/// only needs to be done once for a given model, it is cheap enough to run ahead of time or occasionally
+/// at runtime, but should not be run every control loop iteration with a large iteration count.
+pub fn solve_lqr(
+    a: [[f64; 3]; 3],
+    b: [[f64; 2]; 3],
+    state_weights: [f64; 3],
+    input_weights: [f64; 2],
+    max_iterations: usize,
+    tolerance: f64,
+) -> [[f64; 3]; 2] {
+    let p = diagonal(state_weights);
+
+    iterate_riccati(a, b, state_weights, input_weights, p, max_iterations, tolerance).0
+}
+
+/// A diagonal matrix with the given entries.
+fn diagonal(entries: [f64; 3]) -> [[f64; 3]; 3] {
+    let mut matrix = [[0.0; 3]; 3];
+    for i in 0..3 {
+        matrix[i][i] = entries[i];
+    }
+
+    matrix
+}
+
+/// Iterate the discrete-time algebraic Riccati equation starting from a cost-to-go matrix `P`, returning
+/// the gain matrix `K` and the final `P`.
+///
+/// Starting from the `P` of a nearby model converges in far fewer iterations than starting from `Q`.
+fn iterate_riccati(
+    a: [[f64; 3]; 3],
+    b: [[f64; 2]; 3],
+    state_weights: [f64; 3],
+    input_weights: [f64; 2],
+    mut p: [[f64; 3]; 3],
+    max_iterations: usize,
+    tolerance: f64,
+) -> ([[f64; 3]; 2], [[f64; 3]; 3]) {
+    let mut k = [[0.0; 3]; 2];
+
+    for _ in 0..max_iterations {
+        // P * A and P * B
+        let mut pa = [[0.0; 3]; 3];
+        let mut pb = [[0.0; 2]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                pa[i][j] = (0..3).map(|n| p[i][n] * a[n][j]).sum();
+            }
+            for j in 0..2 {
+                pb[i][j] = (0..3).map(|n| p[i][n] * b[n][j]).sum();
+            }
+        }
+
+        // S = R + Bᵀ P B, and its inverse.
+        let mut s = [[0.0; 2]; 2];
+        for i in 0..2 {
+            for j in 0..2 {
+                s[i][j] = (0..3).map(|n| b[n][i] * pb[n][j]).sum();
+            }
+            s[i][i] += input_weights[i];
+        }
+        let determinant = s[0][0] * s[1][1] - s[0][1] * s[1][0];
+        if determinant.abs() < f64::EPSILON {
+            break;
+        }
+        let s_inverse = [
+            [s[1][1] / determinant, -s[0][1] / determinant],
+            [-s[1][0] / determinant, s[0][0] / determinant],
+        ];
+
+        // K = S⁻¹ Bᵀ P A
+        let mut bpa = [[0.0; 3]; 2];
+        for i in 0..2 {
+            for j in 0..3 {
+                bpa[i][j] = (0..3).map(|n| b[n][i] * pa[n][j]).sum();
+            }
+        }
+        let mut next_k = [[0.0; 3]; 2];
+        for i in 0..2 {
+            for j in 0..3 {
+                next_k[i][j] = s_inverse[i][0] * bpa[0][j] + s_inverse[i][1] * bpa[1][j];
+            }
+        }
+
+        // P = Q + Aᵀ P A - Aᵀ P B K
+        let mut next_p = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                let apa: f64 = (0..3).map(|n| a[n][i] * pa[n][j]).sum();
+                let apbk: f64 = (0..2).map(|m| bpa[m][i] * next_k[m][j]).sum();
+
+                next_p[i][j] = apa - apbk;
+            }
+            next_p[i][i] += state_weights[i];
+        }
+
+        let change = (0..2)
+            .flat_map(|i| (0..3).map(move |j| (i, j)))
+            .map(|(i, j)| (next_k[i][j] - k[i][j]).abs())
+            .fold(0.0, f64::max);
+
+        k = next_k;
+        p = next_p;
+
+        if change <= tolerance {
+            break;
+        }
+    }
+
+    (k, p)
+}
+
+/// A linear-quadratic regulator (LQR) for tracking a trajectory with a differential drive.
+///
+/// LQR computes optimal feedback gains for a linear model of a system given how heavily to penalize error in each
+/// state (`state_weights`) and effort in each input (`input_weights`). Here, the state is the robot's pose error
+/// relative to a reference point on a trajectory, expressed in the reference point's frame as (forward error,
+/// leftward error, heading error). The inputs are corrections to the reference's linear and angular velocity.
+///
+/// Unlike using separate drive and turn [`PIDController`](crate::controller::PIDController)s, a single LQR
+/// accounts for how the two axes interact, such as how turning corrects sideways error while moving.
+///
+/// Since the model is linearized around the reference's velocities, the gains are re-solved whenever the
+/// reference velocity changes by more than `relinearize_threshold`. Gains can also be computed ahead of time
+/// with [`LqrController::solve_gains`] and set with [`LqrController::set_gains`].
+///
+/// # Performance
+///
+/// Each Riccati iteration costs roughly 150 multiply-adds. The first solve runs until the gains converge, which
+/// takes around 200 iterations at a 10 millisecond timestep (and at most 500). Later solves start from the
+/// previous solution and are capped at `iterations` (50 by default), since a small change in velocity only
+/// changes the gains slightly. Smaller values of `relinearize_threshold` track the reference's linearization more
+/// closely at the cost of re-solving more often, and a threshold of `0.0` re-solves whenever the reference
+/// velocity changes at all.
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub struct LqrController {
+    /// The cost weights of forward, leftward, and heading error.
+    pub state_weights: [f64; 3],
+
+    /// The cost weights of linear and angular velocity corrections.
+    pub input_weights: [f64; 2],
+
+    /// The timestep used to discretize the model, which should match the control loop's period.
+    pub timestep: Duration,
+
+    /// How much the reference velocity must change before the gains are re-solved.
+    pub relinearize_threshold: f64,
+
+    /// The maximum number of Riccati iterations used to re-solve the gains when relinearizing.
+    ///
+    /// Each re-solve continues from the previous solution, so gains that don't fully converge in one
+    /// re-solve keep improving with each later one.
+    pub iterations: usize,
+
+    gains: [[f64; 3]; 2],
+    cost_to_go: Option<[[f64; 3]; 3]>,
+    linearization: Option<(f64, f64)>,
+    reference_velocity: (f64, f64),
+}
+
+impl LqrController {
+    /// Construct a new [`LqrController`] from cost weights, a discretization timestep, and how much the
+    /// reference velocity must change before the gains are re-solved.
+    pub fn new(
+        state_weights: [f64; 3],
+        input_weights: [f64; 2],
+        timestep: Duration,
+        relinearize_threshold: f64,
+    ) -> Self {
+        Self {
+            state_weights,
+            input_weights,
+            timestep,
+            relinearize_threshold,
+            iterations: 50,
+            gains: [[0.0; 3]; 2],
+            cost_to_go: None,
+            linearization: None,
+            reference_velocity: (0.0, 0.0),
+        }
+    }
+
+    /// Linearize the pose error dynamics around a reference linear and angular velocity.
+    pub fn linearize(
+        linear_velocity: f64,
+        angular_velocity: f64,
+        timestep: Duration,
+    ) -> ([[f64; 3]; 3], [[f64; 2]; 3]) {
+        let dt = timestep.as_secs_f64();
+
+        (
+            [
+                [1.0, angular_velocity * dt, 0.0],
+                [-angular_velocity * dt, 1.0, linear_velocity * dt],
+                [0.0, 0.0, 1.0],
+            ],
+            [[dt, 0.0], [0.0, 0.0], [0.0, dt]],
+        )
+    }
+
+    /// Solve for the gains at a reference linear and angular velocity using this controller's weights.
+    ///
+    /// This always solves from scratch, iterating up to 500 times.
+    pub fn solve_gains(&self, linear_velocity: f64, angular_velocity: f64) -> [[f64; 3]; 2] {
+        let (a, b) = Self::linearize(linear_velocity, angular_velocity, self.timestep);
+
+        solve_lqr(a, b, self.state_weights, self.input_weights, 500, 1e-9)
+    }
+
+    /// Get the current gain matrix.
+    pub fn gains(&self) -> [[f64; 3]; 2] {
+        self.gains
+    }
+
+    /// Sets the gain matrix, such as to gains solved ahead of time. These gains will be used until
+    /// the reference velocity moves more than `relinearize_threshold` from `linear_velocity` and
+    /// `angular_velocity`.
+    pub fn set_gains(&mut self, gains: [[f64; 3]; 2], linear_velocity: f64, angular_velocity: f64) {
+        self.gains = gains;
+        self.linearization = Some((linear_velocity, angular_velocity));
+    }
+
+    /// Sets the reference linear and angular velocity that the model is linearized around.
+    pub fn set_reference_velocity(&mut self, linear_velocity: f64, angular_velocity: f64) {
+        self.reference_velocity = (linear_velocity, angular_velocity);
+    }
+
+    /// Compute a chassis velocity as a tuple (`linear_velocity`, `angular_velocity`).
+    ///
+    /// - `pose` is the robot's current (`position`, `heading`), such as from [`Tracking`](crate::tracking::Tracking).
+    /// - `reference` is the point the robot should be at on the trajectory.
+    ///
+    /// The output can be converted into motor power with
+    /// [`DifferentialDrivetrain::control_velocity`](crate::drivetrain::DifferentialDrivetrain::control_velocity).
+    pub fn track(&mut self, pose: (Vec2, f64), reference: TrajectoryPoint) -> (f64, f64) {
+        let (position, heading) = pose;
+
+        let error = (position - reference.position).rotate(-reference.heading);
+        let heading_error = normalize_angle(heading - reference.heading);
+
+        self.set_reference_velocity(reference.linear_velocity, reference.angular_velocity);
+        let (linear_correction, angular_correction) =
+            self.update([error.x, error.y, heading_error], self.timestep);
+
+        (
+            reference.linear_velocity + linear_correction,
+            reference.angular_velocity + angular_correction,
+        )
+    }
+}
+
+impl FeedbackController for LqrController {
+    /// The pose error (forward, leftward, heading) relative to the reference, in the reference's frame.
+    type Input = [f64; 3];
+
+    /// Corrections to the reference (`linear_velocity`, `angular_velocity`).
+    type Output = (f64, f64);
+
+    fn update(&mut self, error: Self::Input, _dt: Duration) -> Self::Output {
+        let (linear_velocity, angular_velocity) = self.reference_velocity;
+
+        let needs_solve = match self.linearization {
+            Some((linearized_linear, linearized_angular)) => {
+                (linear_velocity - linearized_linear).abs() > self.relinearize_threshold
+                    || (angular_velocity - linearized_angular).abs() > self.relinearize_threshold
+            }
+            None => true,
+        };
+        if needs_solve {
+            let (a, b) = Self::linearize(linear_velocity, angular_velocity, self.timestep);
+
+            // Warm start from the previous solution if there is one, since the model has only changed slightly.
+            let (p, max_iterations) = match self.cost_to_go {
+                Some(p) => (p, self.iterations),
+                None => (diagonal(self.state_weights), 500),
+            };
+
+            let (gains, p) = iterate_riccati(
+                a,
+                b,
+                self.state_weights,
+                self.input_weights,
+                p,
+                max_iterations,
+                1e-9,
+            );
+
+            self.cost_to_go = Some(p);
+            self.set_gains(gains, linear_velocity, angular_velocity);
+        }
+
+        let k = self.gains;
+
+        (
+            -(k[0][0] * error[0] + k[0][1] * error[1] + k[0][2] * error[2]),
+            -(k[1][0] * error[0] + k[1][1] * error[1] + k[1][2] * error[2]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chassis::ChassisModel, controller::SimpleMotorFeedforward, mpc::DifferentialDriveSimulation,
+    };
+    use core::f64::consts::FRAC_PI_2;
+
+    const DT: Duration = Duration::from_millis(10);
+
+    fn controller(relinearize_threshold: f64) -> LqrController {
+        LqrController::new([1.0, 1.0, 20.0], [0.01, 0.1], DT, relinearize_threshold)
+    }
+
+    fn largest_difference(a: [[f64; 3]; 2], b: [[f64; 3]; 2]) -> f64 {
+        (0..2)
+            .flat_map(|i| (0..3).map(move |j| (a[i][j] - b[i][j]).abs()))
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn gains_stabilize_error() {
+        let (a, b) = LqrController::linearize(20.0, 0.5, DT);
+        let k = controller(0.0).solve_gains(20.0, 0.5);
+        let mut error = [1.0, -2.0, 0.3];
+
+        // Simulate the closed loop error dynamics `e = (A - BK) e`.
+        for _ in 0..2000 {
+            let input = [
+                -(0..3).map(|j| k[0][j] * error[j]).sum::<f64>(),
+                -(0..3).map(|j| k[1][j] * error[j]).sum::<f64>(),
+            ];
+
+            error = core::array::from_fn(|i| {
+                (0..3).map(|j| a[i][j] * error[j]).sum::<f64>()
+                    + b[i][0] * input[0]
+                    + b[i][1] * input[1]
+            });
+        }
+
+        assert!(error.iter().all(|error| error.abs() < 1e-6));
+    }
+
+    #[test]
+    fn stationary_gains_are_finite() {
+        let k = controller(0.0).solve_gains(0.0, 0.0);
+
+        assert!(k.iter().flatten().all(|gain| gain.is_finite()));
+
+        // With no velocity, sideways error can't be corrected and is ignored.
+        assert_eq!(k[0][1], 0.0);
+        assert_eq!(k[1][1], 0.0);
+    }
+
+    #[test]
+    fn warm_started_solve_matches_full_solve() {
+        let mut lqr = controller(0.0);
+
+        lqr.set_reference_velocity(20.0, 0.5);
+        lqr.update([0.0; 3], DT);
+
+        // The re-solve is capped at `iterations`, but starts much closer than a solve from scratch.
+        lqr.set_reference_velocity(21.0, 0.55);
+        lqr.update([0.0; 3], DT);
+
+        let (a, b) = LqrController::linearize(21.0, 0.55, DT);
+        let exact = lqr.solve_gains(21.0, 0.55);
+        let cold = solve_lqr(a, b, lqr.state_weights, lqr.input_weights, lqr.iterations, 1e-9);
+
+        let warm_error = largest_difference(lqr.gains(), exact);
+        assert!(warm_error < 0.01);
+        assert!(warm_error < largest_difference(cold, exact) / 10.0);
+    }
+
+    #[test]
+    fn relinearizes_past_threshold() {
+        let mut lqr = controller(1.0);
+
+        lqr.set_reference_velocity(20.0, 0.0);
+        lqr.update([0.0; 3], DT);
+        let gains = lqr.gains();
+
+        lqr.set_reference_velocity(20.5, 0.0);
+        lqr.update([0.0; 3], DT);
+        assert_eq!(lqr.gains(), gains);
+
+        lqr.set_reference_velocity(22.0, 0.0);
+        lqr.update([0.0; 3], DT);
+        assert_ne!(lqr.gains(), gains);
+    }
+
+    #[test]
+    fn tracks_circle_from_offset_start() {
+        let model = ChassisModel::new(12.0, SimpleMotorFeedforward::new(2.0, 1.5, 0.2));
+        let mut simulation = DifferentialDriveSimulation::new(model, Vec2::new(0.0, 4.0), 0.3);
+        let mut lqr = controller(0.1);
+
+        // A counterclockwise circle with a radius of 30 driven at 20 units per second.
+        let (radius, velocity) = (30.0, 20.0);
+        let angular_velocity = velocity / radius;
+        let reference = |time: f64| {
+            let angle = angular_velocity * time - FRAC_PI_2;
+
+            TrajectoryPoint::new(
+                Vec2::new(radius * angle.cos(), radius + radius * angle.sin()),
+                angular_velocity * time,
+                velocity,
+                angular_velocity,
+            )
+        };
+
+        let mut error = 0.0;
+        for i in 0..600 {
+            let time = i as f64 * DT.as_secs_f64();
+            let (linear_velocity, angular_velocity) =
+                lqr.track((simulation.position, simulation.heading), reference(time));
+
+            simulation.step(model.wheel_powers(linear_velocity, angular_velocity), DT);
+            error = simulation.position.distance(reference(time + DT.as_secs_f64()).position);
+        }
+
+        assert!(error < 1.0);
+    }
+}
@@ -51,9 +51,9 @@ impl Robot for Robor {
     fn autonomous(&mut self, _ctx: Context) {
         let dt = &mut self.drivetrain;
 
-        dt.drive_distance(10.0);
+        dt.drive_distance(10.0, None);
         dt.wait_until_settled();
-        dt.turn_to_angle(90.0.to_degrees());
+        dt.turn_to_angle(90.0.to_degrees(), None);
         dt.wait_until_settled();
     }
 
@@ -1,17 +1,35 @@
 pub mod vec2;
 pub mod pursuit;
+pub mod stanley;
 
 pub use vec2::Vec2;
 pub use pursuit::*;
+pub use stanley::*;
 
-use core::f64::consts::FRAC_2_PI;
+use core::f64::consts::PI;
 use num_traits::real::Real;
+
+/// Wrap a value into the range from `min` (inclusive) to `max` (exclusive).
+///
+/// Values outside of the range will wrap around to the other end, similar to how
+/// an angle of 370° is equivalent to 10°.
+pub fn wrap(value: f64, min: f64, max: f64) -> f64 {
+    let range = max - min;
+    let offset = (value - min) % range;
+
+    if offset < 0.0 {
+        min + offset + range
+    } else {
+        min + offset
+    }
+}
+
 /// Constrain an angle in radians from -π to +π.
 ///
 /// This preserves the angle's direction while keeping it within minimum constrains,
 /// allowing certain operations to be performed easier.
 pub fn normalize_angle(angle: f64) -> f64 {
-    angle % FRAC_2_PI
+    wrap(angle, -PI, PI)
 }
 
 /// Normalizes the ratio of voltages between two motors.
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+use core::time::Duration;
 use num_traits::real::Real;
 
 use crate::math::Vec2;
@@ -17,48 +19,628 @@ pub enum LineCircleIntersections {
 }
 
 impl LineCircleIntersections {
-	
 	/// Compute the points of intersection between a line extending infinitely in both directions
 	/// and a circle defined by a center and radius.
 	/// 
 	/// The result is returned as an instance of [`Self`], having either no intersections ([`Self::None`]),
-	/// one intersection as a tangent line ([`Self::OneIntersection`]), or two intersections as a secant line ([`Self::TwoIntersections`]).
+	/// one intersection as a tangent line ([`Self::Tangent`]), or two intersections as a secant line ([`Self::Secant`]).
 	pub fn compute(line: (Vec2, Vec2), circle: (Vec2, f64)) -> Self {
-		let (start, end) = line;
-		let (center, radius) = circle;
-
-		let offset_1 = start - center;
-		let offset_2 = end - center;
-
-		let dx = offset_2.x - offset_1.x;
-		let dy = offset_2.y - offset_1.y;
-		let dr = offset_1.distance(offset_2);
-		let d = offset_1.cross(offset_2);
-		let discriminant = radius.powi(2) * dr.powi(2) - d.powi(2);
-
-		if discriminant >= 0.0 {
-			let solution_1 = Vec2::new(
-				(d * dy + dy.signum() * dx * discriminant.sqrt()) / dr.powi(2),
-				(-d * dx + dy.abs() * discriminant.sqrt()) / dr.powi(2)
-			) + center;
-			let solution_2 = Vec2::new(
-				(d * dy - dy.signum() * dx * discriminant.sqrt()) / dr.powi(2),
-				(-d * dx - dy.abs() * discriminant.sqrt()) / dr.powi(2)
-			);
+		match Self::solve(line, circle) {
+			Some((t1, t2)) if t1 == t2 => Self::Tangent(line.0.lerp(line.1, t1)),
+			Some((t1, t2)) => Self::Secant(line.0.lerp(line.1, t1), line.0.lerp(line.1, t2)),
+			None => Self::None,
 		}
-
-		todo!()
 	}
 
 	/// Compute the points of intersection between a line segment formed by two points
 	/// and a circle defined by a center and radius.
 	/// 
 	/// The result is returned as an instance of [`Self`], having either no intersections ([`Self::None`]),
-	/// one intersection ([`Self::OneIntersection`]), or two intersections ([`Self::TwoIntersections`]).
+	/// one intersection ([`Self::Tangent`]), or two intersections ([`Self::Secant`]).
 	/// 
 	/// This differs from [`LineCircleIntersections::compute`] in that it performs a bounds check to ensure that
 	/// the intersections are contained within the line segment, which has a defined start and endpoint.
 	pub fn compute_bounded(line: (Vec2, Vec2), circle: (Vec2, f64)) -> Self {
-		todo!()
+		let (t1, t2) = match Self::solve(line, circle) {
+			Some(solutions) => solutions,
+			None => return Self::None,
+		};
+
+		let in_bounds = |t: f64| (0.0..=1.0).contains(&t);
+
+		match (in_bounds(t1), in_bounds(t2)) {
+			(true, true) if t1 != t2 => Self::Secant(line.0.lerp(line.1, t1), line.0.lerp(line.1, t2)),
+			(true, _) => Self::Tangent(line.0.lerp(line.1, t1)),
+			(false, true) => Self::Tangent(line.0.lerp(line.1, t2)),
+			(false, false) => Self::None,
+		}
+	}
+
+	/// Solve for the intersections as fractions of the distance from the line's start to its end.
+	fn solve(line: (Vec2, Vec2), circle: (Vec2, f64)) -> Option<(f64, f64)> {
+		let (start, end) = line;
+		let (center, radius) = circle;
+
+		// Substituting the line `start + t * direction` into the circle's equation gives a quadratic in `t`.
+		let direction = end - start;
+		let offset = start - center;
+
+		let a = direction.dot(direction);
+		let b = 2.0 * offset.dot(direction);
+		let c = offset.dot(offset) - radius.powi(2);
+		let discriminant = b.powi(2) - 4.0 * a * c;
+
+		if a == 0.0 || discriminant < 0.0 {
+			return None;
+		}
+
+		let root = discriminant.sqrt();
+
+		Some(((-b - root) / (2.0 * a), (-b + root) / (2.0 * a)))
+	}
+}
+
+/// Compute the curvature of the circular arc that starts at a pose and passes through a point.
+///
+/// Positive curvature represents a counterclockwise (leftward) arc. The curvature is the inverse
+/// of the arc's radius.
+pub fn curvature_to_point(position: Vec2, heading: f64, point: Vec2) -> f64 {
+	// The point relative to the pose, where x is forwards and y is to the left.
+	let local = (point - position).rotate(-heading);
+	let distance_squared = local.dot(local);
+
+	if distance_squared == 0.0 {
+		0.0
+	} else {
+		2.0 * local.y / distance_squared
+	}
+}
+
+/// Limits and tuning constants for adaptive pure pursuit.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct PursuitConstraints {
+	/// The maximum velocity along the path.
+	pub max_velocity: f64,
+
+	/// The maximum acceleration and deceleration along the path.
+	pub max_acceleration: f64,
+
+	/// Controls how much the robot slows down around curves. The target velocity at each waypoint is
+	/// limited to this value divided by the path's curvature at that waypoint.
+	pub curvature_velocity: f64,
+
+	/// How much the lookahead distance grows with velocity. The lookahead distance is the minimum
+	/// lookahead distance plus this gain multiplied by the current velocity.
+	pub lookahead_gain: f64,
+
+	/// The maximum lookahead distance.
+	pub max_lookahead_distance: f64,
+}
+
+impl PursuitConstraints {
+	/// Construct a new set of [`PursuitConstraints`].
+	pub fn new(
+		max_velocity: f64,
+		max_acceleration: f64,
+		curvature_velocity: f64,
+		lookahead_gain: f64,
+		max_lookahead_distance: f64,
+	) -> Self {
+		Self {
+			max_velocity,
+			max_acceleration,
+			curvature_velocity,
+			lookahead_gain,
+			max_lookahead_distance,
+		}
+	}
+}
+
+/// A path of waypoints with a target velocity at each waypoint.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PursuitPath {
+	waypoints: Vec<Vec2>,
+	velocities: Vec<f64>,
+	distances: Vec<f64>,
+}
+
+impl PursuitPath {
+	/// Construct a new [`PursuitPath`] from a series of waypoints.
+	///
+	/// Each waypoint's target velocity is limited by the path's curvature at that waypoint, then
+	/// lowered further so that the robot can decelerate to a stop at the end of the path. Between
+	/// waypoints, the target velocity is interpolated (see [`PursuitPath::velocity_at`]). Since
+	/// curvature is estimated from neighboring waypoints, closely and evenly spaced waypoints will
+	/// produce smoother velocities.
+	pub fn new(waypoints: Vec<Vec2>, constraints: PursuitConstraints) -> Self {
+		let mut distances = Vec::with_capacity(waypoints.len());
+		let mut travelled = 0.0;
+
+		for (i, waypoint) in waypoints.iter().enumerate() {
+			if i > 0 {
+				travelled += waypoint.distance(waypoints[i - 1]);
+			}
+
+			distances.push(travelled);
+		}
+
+		let mut velocities: Vec<f64> = (0..waypoints.len())
+			.map(|i| {
+				let curvature = Self::curvature_at(&waypoints, i);
+
+				if curvature > 0.0 {
+					constraints.max_velocity.min(constraints.curvature_velocity / curvature)
+				} else {
+					constraints.max_velocity
+				}
+			})
+			.collect();
+
+		// Limit each waypoint's velocity so that the robot can decelerate to the next waypoint's
+		// velocity in time, ending at rest.
+		if let Some(last) = velocities.last_mut() {
+			*last = 0.0;
+		}
+		for i in (0..velocities.len().saturating_sub(1)).rev() {
+			let distance = distances[i + 1] - distances[i];
+			let reachable =
+				(velocities[i + 1].powi(2) + 2.0 * constraints.max_acceleration * distance).sqrt();
+
+			velocities[i] = velocities[i].min(reachable);
+		}
+
+		Self {
+			waypoints,
+			velocities,
+			distances,
+		}
+	}
+
+	/// Estimate the curvature of a path at a waypoint from the circle passing through it and its neighbors.
+	fn curvature_at(waypoints: &[Vec2], index: usize) -> f64 {
+		if index == 0 || index + 1 >= waypoints.len() {
+			return 0.0;
+		}
+
+		let (a, b, c) = (waypoints[index - 1], waypoints[index], waypoints[index + 1]);
+		let product = a.distance(b) * b.distance(c) * c.distance(a);
+
+		if product == 0.0 {
+			0.0
+		} else {
+			2.0 * (b - a).cross(c - a).abs() / product
+		}
+	}
+
+	/// Get the path's waypoints.
+	pub fn waypoints(&self) -> &[Vec2] {
+		&self.waypoints
+	}
+
+	/// Get the target velocity at each waypoint.
+	pub fn velocities(&self) -> &[f64] {
+		&self.velocities
+	}
+
+	/// Get the distance along the path to each waypoint.
+	pub fn distances(&self) -> &[f64] {
+		&self.distances
+	}
+
+	/// Get the target velocity at a distance along the path.
+	///
+	/// Between two waypoints, the velocity changes at a constant acceleration from one waypoint's target
+	/// velocity to the next, so the robot only comes to a stop at the very end of the path no matter how
+	/// far apart the waypoints are.
+	pub fn velocity_at(&self, distance: f64) -> f64 {
+		let next = self.distances.partition_point(|&waypoint_distance| waypoint_distance <= distance);
+
+		match (next.checked_sub(1), self.velocities.get(next)) {
+			(Some(previous), Some(next_velocity)) => {
+				let (start, end) = (self.distances[previous], self.distances[next]);
+				let t = (distance - start) / (end - start);
+
+				(self.velocities[previous].powi(2) * (1.0 - t) + next_velocity.powi(2) * t).sqrt()
+			}
+			// Before the start of the path.
+			(None, Some(first_velocity)) => *first_velocity,
+			// At or past the end of the path.
+			_ => self.velocities.last().copied().unwrap_or(0.0),
+		}
+	}
+
+	/// Find the index of the waypoint closest to a position, searching only from a starting index onwards
+	/// so that the robot never moves backwards along the path.
+	pub fn closest_index(&self, position: Vec2, start: usize) -> usize {
+		let mut closest = start.min(self.waypoints.len().saturating_sub(1));
+
+		for i in closest..self.waypoints.len() {
+			if self.waypoints[i].distance(position) < self.waypoints[closest].distance(position) {
+				closest = i;
+			}
+		}
+
+		closest
+	}
+
+	/// Find the point on the path closest to a position, searching only the segments adjacent to a waypoint.
+	///
+	/// Returns the index of the segment containing the point (where segment `i` runs from waypoint `i` to
+	/// waypoint `i + 1`) and the point's distance along the path.
+	pub fn project(&self, position: Vec2, waypoint: usize) -> (usize, f64) {
+		let mut closest = (0, 0.0);
+		let mut closest_distance = f64::MAX;
+
+		let last_segment = self.waypoints.len().saturating_sub(2);
+		for i in waypoint.saturating_sub(1).min(last_segment)..=waypoint.min(last_segment) {
+			let (start, end) = match (self.waypoints.get(i), self.waypoints.get(i + 1)) {
+				(Some(start), Some(end)) => (*start, *end),
+				_ => break,
+			};
+			let direction = end - start;
+			let length = direction.length();
+
+			let t = if length == 0.0 {
+				0.0
+			} else {
+				((position - start).dot(direction) / length.powi(2)).clamp(0.0, 1.0)
+			};
+			let distance = start.lerp(end, t).distance(position);
+
+			if distance < closest_distance {
+				closest_distance = distance;
+				closest = (i, self.distances[i] + t * length);
+			}
+		}
+
+		closest
+	}
+
+	/// Get the total length of the path.
+	pub fn length(&self) -> f64 {
+		self.distances.last().copied().unwrap_or(0.0)
 	}
-}
\ No newline at end of file
+
+	/// Find the furthest point along the path that intersects a lookahead circle.
+	///
+	/// Only intersections at or beyond `start`, a fractional waypoint index, are considered. Returns the
+	/// point and its fractional waypoint index, or `None` if the circle does not intersect the path.
+	pub fn lookahead_point(&self, center: Vec2, radius: f64, start: f64) -> Option<(Vec2, f64)> {
+		let mut lookahead = None;
+
+		for i in (start.max(0.0) as usize)..self.waypoints.len().saturating_sub(1) {
+			let segment = (self.waypoints[i], self.waypoints[i + 1]);
+			let direction = segment.1 - segment.0;
+
+			let points = match LineCircleIntersections::compute_bounded(segment, (center, radius)) {
+				LineCircleIntersections::Secant(point_1, point_2) => [Some(point_1), Some(point_2)],
+				LineCircleIntersections::Tangent(point) => [Some(point), None],
+				LineCircleIntersections::None => [None, None],
+			};
+
+			for point in points.into_iter().flatten() {
+				let index = i as f64 + (point - segment.0).dot(direction) / direction.dot(direction);
+
+				if index >= start && lookahead.map_or(true, |(_, furthest)| index > furthest) {
+					lookahead = Some((point, index));
+				}
+			}
+
+			// Once the path leaves the lookahead circle, later intersections belong to a different part
+			// of the path that happens to pass nearby.
+			if lookahead.is_some() && segment.1.distance(center) > radius {
+				break;
+			}
+		}
+
+		lookahead
+	}
+}
+
+/// An adaptive pure pursuit path follower.
+///
+/// Pure pursuit follows a path by repeatedly steering along the circular arc that connects the robot to a
+/// "lookahead point" some distance ahead of it on the path. The lookahead distance grows with velocity to keep
+/// the robot stable at speed, and the robot's velocity is limited at each waypoint so that it slows down for
+/// tight curves and the end of the path.
+///
+/// Each update produces a linear and angular chassis velocity, which can be converted into motor power with a
+/// [`ChassisModel`](crate::chassis::ChassisModel).
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PurePursuit {
+	path: PursuitPath,
+	constraints: PursuitConstraints,
+	lookahead_distance: f64,
+	closest_index: usize,
+	lookahead_index: f64,
+	lookahead_point: Vec2,
+	velocity: f64,
+	progress: PathProgress,
+}
+
+/// How far along its path a [`PurePursuit`] follower is.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct PathProgress {
+	/// The index of the path segment the robot is on, where segment `i` runs from waypoint `i`
+	/// to waypoint `i + 1`.
+	pub segment_index: usize,
+
+	/// The distance travelled along the path.
+	pub distance_travelled: f64,
+
+	/// The distance left until the end of the path.
+	pub distance_remaining: f64,
+}
+
+impl PurePursuit {
+	/// Construct a new [`PurePursuit`] follower for a series of waypoints with a minimum lookahead distance.
+	pub fn new(waypoints: Vec<Vec2>, lookahead_distance: f64, constraints: PursuitConstraints) -> Self {
+		let path = PursuitPath::new(waypoints, constraints);
+		let lookahead_point = path.waypoints().first().copied().unwrap_or_default();
+
+		Self {
+			path,
+			constraints,
+			lookahead_distance,
+			lookahead_point,
+			..Default::default()
+		}
+	}
+
+	/// Get the path being followed.
+	pub fn path(&self) -> &PursuitPath {
+		&self.path
+	}
+
+	/// Get the index of the waypoint closest to the robot as of the last update.
+	pub fn closest_index(&self) -> usize {
+		self.closest_index
+	}
+
+	/// Get how far along the path the robot is as of the last update.
+	pub fn progress(&self) -> PathProgress {
+		self.progress
+	}
+
+	/// Get the point on the path being steered towards as of the last update.
+	pub fn lookahead_point(&self) -> Vec2 {
+		self.lookahead_point
+	}
+
+	/// Get the current lookahead distance, which grows with velocity.
+	pub fn current_lookahead_distance(&self) -> f64 {
+		let max_lookahead_distance = self.constraints.max_lookahead_distance.max(self.lookahead_distance);
+
+		(self.lookahead_distance + self.constraints.lookahead_gain * self.velocity.abs())
+			.max(self.lookahead_distance)
+			.min(max_lookahead_distance)
+	}
+
+	/// Returns `true` if the end of the path is within the lookahead circle of a position, meaning
+	/// there is no more path to look ahead to.
+	pub fn is_finished(&self, position: Vec2) -> bool {
+		match self.path.waypoints().last() {
+			Some(end) => end.distance(position) <= self.current_lookahead_distance(),
+			None => true,
+		}
+	}
+
+	/// Compute a chassis velocity as a tuple (`linear_velocity`, `angular_velocity`) for the robot's current pose.
+	///
+	/// Angular velocity is in radians per unit of time, increasing counterclockwise.
+	pub fn update(&mut self, position: Vec2, heading: f64, dt: Duration) -> (f64, f64) {
+		self.closest_index = self.path.closest_index(position, self.closest_index);
+
+		let (segment_index, distance_travelled) = self.path.project(position, self.closest_index);
+		self.progress = PathProgress {
+			segment_index,
+			distance_travelled,
+			distance_remaining: self.path.length() - distance_travelled,
+		};
+
+		// Accelerate towards the path's target velocity at the robot's position, but never exceed it.
+		self.velocity = self
+			.path
+			.velocity_at(distance_travelled)
+			.min(self.velocity + self.constraints.max_acceleration * dt.as_secs_f64());
+
+		// If the lookahead circle doesn't intersect the path, the robot has presumably been knocked off course,
+		// so the last known lookahead point is kept to allow the robot to get back on the path.
+		let lookahead_distance = self.current_lookahead_distance();
+		if let Some((point, index)) =
+			self.path.lookahead_point(position, lookahead_distance, self.lookahead_index)
+		{
+			self.lookahead_point = point;
+			self.lookahead_index = index;
+		}
+
+		let curvature = curvature_to_point(position, heading, self.lookahead_point);
+
+		(self.velocity, self.velocity * curvature)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloc::vec;
+
+	const DT: Duration = Duration::from_millis(10);
+
+	/// An L-shaped path that drives 40 units forwards, then turns left for 40 units.
+	fn l_path() -> Vec<Vec2> {
+		(0..=40)
+			.map(|i| Vec2::new(i as f64, 0.0))
+			.chain((1..=40).map(|i| Vec2::new(40.0, i as f64)))
+			.collect()
+	}
+
+	#[test]
+	fn line_circle_intersections() {
+		let circle = (Vec2::new(0.0, 0.0), 1.0);
+
+		assert_eq!(
+			LineCircleIntersections::compute_bounded((Vec2::new(-2.0, 0.0), Vec2::new(2.0, 0.0)), circle),
+			LineCircleIntersections::Secant(Vec2::new(-1.0, 0.0), Vec2::new(1.0, 0.0))
+		);
+		assert_eq!(
+			LineCircleIntersections::compute_bounded((Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0)), circle),
+			LineCircleIntersections::Tangent(Vec2::new(1.0, 0.0))
+		);
+		assert_eq!(
+			LineCircleIntersections::compute_bounded((Vec2::new(2.0, 0.0), Vec2::new(3.0, 0.0)), circle),
+			LineCircleIntersections::None
+		);
+		assert_eq!(
+			LineCircleIntersections::compute((Vec2::new(2.0, 0.0), Vec2::new(3.0, 0.0)), circle),
+			LineCircleIntersections::Secant(Vec2::new(-1.0, 0.0), Vec2::new(1.0, 0.0))
+		);
+		assert_eq!(
+			LineCircleIntersections::compute((Vec2::new(0.0, 2.0), Vec2::new(1.0, 2.0)), circle),
+			LineCircleIntersections::None
+		);
+	}
+
+	#[test]
+	fn curvature_is_positive_to_the_left() {
+		let origin = Vec2::new(0.0, 0.0);
+
+		assert_eq!(curvature_to_point(origin, 0.0, Vec2::new(5.0, 0.0)), 0.0);
+		assert!((curvature_to_point(origin, 0.0, Vec2::new(1.0, 1.0)) - 1.0).abs() < 1e-9);
+		assert!((curvature_to_point(origin, 0.0, Vec2::new(1.0, -1.0)) + 1.0).abs() < 1e-9);
+		assert_eq!(curvature_to_point(origin, 0.0, origin), 0.0);
+	}
+
+	#[test]
+	fn path_velocities_slow_for_curves_and_end() {
+		let path = PursuitPath::new(l_path(), PursuitConstraints::new(30.0, 60.0, 20.0, 0.0, 0.0));
+
+		assert_eq!(path.length(), 80.0);
+		assert_eq!(path.distances()[41], 41.0);
+		assert_eq!(*path.velocities().last().unwrap(), 0.0);
+
+		// Straight sections reach the maximum velocity, while the corner is slowed down.
+		assert_eq!(path.velocities()[10], 30.0);
+		assert!(path.velocities()[40] < 30.0);
+
+		// Every waypoint can decelerate to the next waypoint's velocity.
+		for i in 0..path.velocities().len() - 1 {
+			let (current, next) = (path.velocities()[i], path.velocities()[i + 1]);
+
+			assert!(current.powi(2) <= next.powi(2) + 2.0 * 60.0 + 1e-9);
+		}
+	}
+
+	#[test]
+	fn path_projection_and_lookahead() {
+		let path = PursuitPath::new(l_path(), PursuitConstraints::default());
+		let position = Vec2::new(10.3, 0.5);
+
+		assert_eq!(path.closest_index(position, 0), 10);
+		assert_eq!(path.closest_index(position, 20), 20);
+
+		let (segment, distance) = path.project(position, 10);
+		assert_eq!(segment, 10);
+		assert!((distance - 10.3).abs() < 1e-9);
+
+		let (point, index) = path.lookahead_point(Vec2::new(10.0, 0.0), 5.0, 0.0).unwrap();
+		assert!(point.distance(Vec2::new(15.0, 0.0)) < 1e-9);
+		assert!((index - 15.0).abs() < 1e-9);
+
+		assert_eq!(path.lookahead_point(Vec2::new(100.0, 100.0), 5.0, 0.0), None);
+	}
+
+	#[test]
+	fn pure_pursuit_follows_path() {
+		let mut pursuit =
+			PurePursuit::new(l_path(), 6.0, PursuitConstraints::new(30.0, 60.0, 20.0, 0.2, 15.0));
+		let (mut position, mut heading) = (Vec2::new(0.0, 0.0), 0.0);
+
+		for _ in 0..2000 {
+			if pursuit.is_finished(position) {
+				break;
+			}
+
+			let (linear_velocity, angular_velocity) = pursuit.update(position, heading, DT);
+			assert!(linear_velocity <= 30.0);
+
+			position += Vec2::from_polar(linear_velocity * DT.as_secs_f64(), heading);
+			heading += angular_velocity * DT.as_secs_f64();
+
+			// The robot should stay close to the path, cutting the corner by less than the lookahead distance.
+			let first_leg = position.distance(Vec2::new(position.x.min(40.0), 0.0));
+			let second_leg = position.distance(Vec2::new(40.0, position.y.max(0.0)));
+			assert!(first_leg.min(second_leg) < 6.0);
+		}
+
+		assert!(pursuit.is_finished(position));
+		assert!(pursuit.progress().distance_remaining < 15.0);
+	}
+
+	#[test]
+	fn path_velocity_is_interpolated_between_waypoints() {
+		let path = PursuitPath::new(
+			vec![Vec2::new(0.0, 0.0), Vec2::new(48.0, 0.0)],
+			PursuitConstraints::new(30.0, 60.0, 20.0, 0.0, 0.0),
+		);
+
+		assert_eq!(path.velocities(), &[30.0, 0.0]);
+		assert_eq!(path.velocity_at(-1.0), 30.0);
+		assert_eq!(path.velocity_at(0.0), 30.0);
+		assert!((path.velocity_at(24.0) - 450.0.sqrt()).abs() < 1e-9);
+		assert!(path.velocity_at(47.9) > 0.0);
+		assert_eq!(path.velocity_at(48.0), 0.0);
+		assert_eq!(path.velocity_at(100.0), 0.0);
+	}
+
+	#[test]
+	fn pure_pursuit_finishes_sparse_paths() {
+		let paths = [
+			vec![Vec2::new(0.0, 0.0), Vec2::new(48.0, 0.0)],
+			vec![Vec2::new(0.0, 0.0), Vec2::new(24.0, 0.0), Vec2::new(48.0, 24.0)],
+		];
+
+		for waypoints in paths {
+			let end = *waypoints.last().unwrap();
+			let mut pursuit =
+				PurePursuit::new(waypoints, 6.0, PursuitConstraints::new(30.0, 60.0, 20.0, 0.2, 15.0));
+			let (mut position, mut heading) = (Vec2::new(0.0, 0.0), 0.0);
+
+			for _ in 0..2000 {
+				if pursuit.is_finished(position) {
+					break;
+				}
+
+				let (linear_velocity, angular_velocity) = pursuit.update(position, heading, DT);
+
+				position += Vec2::from_polar(linear_velocity * DT.as_secs_f64(), heading);
+				heading += angular_velocity * DT.as_secs_f64();
+			}
+
+			// The robot must not stall partway along the final segment.
+			assert!(pursuit.is_finished(position));
+			assert!(position.distance(end) <= pursuit.current_lookahead_distance());
+		}
+	}
+
+	#[test]
+	fn nan_lookahead_distance_does_not_panic() {
+		let pursuit = PurePursuit::new(l_path(), f64::NAN, PursuitConstraints::default());
+
+		pursuit.current_lookahead_distance();
+	}
+
+	#[test]
+	fn unconstrained_pursuit_still_tracks_lookahead_point() {
+		let waypoints = vec![Vec2::new(0.0, 0.0), Vec2::new(20.0, 0.0)];
+		let mut pursuit = PurePursuit::new(waypoints, 6.0, PursuitConstraints::default());
+
+		assert_eq!(pursuit.update(Vec2::new(0.0, 0.0), 0.0, DT), (0.0, 0.0));
+		assert_eq!(pursuit.current_lookahead_distance(), 6.0);
+		assert!(pursuit.lookahead_point().distance(Vec2::new(6.0, 0.0)) < 1e-9);
+
+		pursuit.update(Vec2::new(10.0, 1.0), 0.0, DT);
+		assert!(pursuit.lookahead_point().x > 10.0);
+		assert!((pursuit.progress().distance_remaining - 10.0).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,215 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+use num_traits::real::Real;
+
+use crate::math::{normalize_angle, PathProgress, PursuitConstraints, PursuitPath, Vec2};
+
+/// A Stanley path follower.
+///
+/// Rather than steering towards a point ahead of it like pure pursuit, the Stanley method steers based on the
+/// path segment closest to the robot. It combines the robot's heading error relative to that segment with a
+/// correction for its cross-track error (sideways distance from the path), so it follows sharp corners closely
+/// instead of cutting them.
+///
+/// Each update produces a linear velocity and a heading correction in radians. The heading correction is the
+/// amount the robot should turn from its current heading, and can be given to a turn controller.
+///
+/// # Tuning
+///
+/// - `gain` controls how aggressively cross-track error is corrected.
+/// - `softening` is added to the velocity when scaling the cross-track correction, preventing the correction
+/// from becoming too large at low speeds.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Stanley {
+	/// The cross-track error gain.
+	pub gain: f64,
+
+	/// The softening constant.
+	pub softening: f64,
+
+	path: PursuitPath,
+	constraints: PursuitConstraints,
+	finish_distance: f64,
+	closest_index: usize,
+	velocity: f64,
+	progress: PathProgress,
+}
+
+impl Stanley {
+	/// Construct a new [`Stanley`] follower for a series of waypoints.
+	///
+	/// Target velocities along the path are computed the same way as for pure pursuit. The follower is
+	/// considered finished once it is within `finish_distance` of the end of the path.
+	pub fn new(
+		waypoints: Vec<Vec2>,
+		gain: f64,
+		softening: f64,
+		finish_distance: f64,
+		constraints: PursuitConstraints,
+	) -> Self {
+		Self {
+			gain,
+			softening,
+			path: PursuitPath::new(waypoints, constraints),
+			constraints,
+			finish_distance,
+			..Default::default()
+		}
+	}
+
+	/// Get the path being followed.
+	pub fn path(&self) -> &PursuitPath {
+		&self.path
+	}
+
+	/// Get how far along the path the robot is as of the last update.
+	pub fn progress(&self) -> PathProgress {
+		self.progress
+	}
+
+	/// Returns `true` if the robot is within the finish distance of the end of the path.
+	pub fn is_finished(&self, position: Vec2) -> bool {
+		match self.path.waypoints().last() {
+			Some(end) => end.distance(position) <= self.finish_distance,
+			None => true,
+		}
+	}
+
+	/// Compute a tuple (`linear_velocity`, `heading_correction`) for the robot's current pose.
+	///
+	/// The heading correction is in radians, increasing counterclockwise.
+	pub fn update(&mut self, position: Vec2, heading: f64, dt: Duration) -> (f64, f64) {
+		self.closest_index = self.path.closest_index(position, self.closest_index);
+
+		let (segment_index, distance_travelled) = self.path.project(position, self.closest_index);
+		self.progress = PathProgress {
+			segment_index,
+			distance_travelled,
+			distance_remaining: self.path.length() - distance_travelled,
+		};
+
+		// Accelerate towards the path's target velocity at the robot's position, but never exceed it.
+		self.velocity = self
+			.path
+			.velocity_at(distance_travelled)
+			.min(self.velocity + self.constraints.max_acceleration * dt.as_secs_f64());
+
+		let waypoints = self.path.waypoints();
+		let (start, end) = match (waypoints.get(segment_index), waypoints.get(segment_index + 1)) {
+			(Some(start), Some(end)) => (*start, *end),
+			_ => return (self.velocity, 0.0),
+		};
+
+		let direction = end - start;
+		if direction.length() == 0.0 {
+			return (self.velocity, 0.0);
+		}
+
+		// The closest point on the segment, and the robot's signed distance to the left of it.
+		let nearest = start + direction.unit() * (distance_travelled - self.path.distances()[segment_index]);
+		let cross_track_error = direction.unit().cross(position - nearest);
+		let heading_error = normalize_angle(direction.angle() - heading);
+
+		(
+			self.velocity,
+			heading_error - (self.gain * cross_track_error).atan2(self.softening + self.velocity.abs()),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloc::vec;
+
+	const DT: Duration = Duration::from_millis(10);
+
+	fn straight() -> Stanley {
+		Stanley::new(
+			vec![Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(20.0, 0.0)],
+			1.0,
+			1.0,
+			1.0,
+			PursuitConstraints::new(10.0, 20.0, 5.0, 0.0, 0.0),
+		)
+	}
+
+	#[test]
+	fn no_correction_on_path() {
+		let (linear_velocity, correction) = straight().update(Vec2::new(5.0, 0.0), 0.0, DT);
+
+		assert!(linear_velocity > 0.0);
+		assert_eq!(correction, 0.0);
+	}
+
+	#[test]
+	fn corrects_heading_and_cross_track_error() {
+		// Facing left of the path turns back clockwise.
+		let (_, correction) = straight().update(Vec2::new(5.0, 0.0), 0.3, DT);
+		assert!((correction + 0.3).abs() < 1e-9);
+
+		// Being left of the path also turns clockwise, and being right turns counterclockwise.
+		let (_, left_correction) = straight().update(Vec2::new(5.0, 1.0), 0.0, DT);
+		let (_, right_correction) = straight().update(Vec2::new(5.0, -1.0), 0.0, DT);
+		assert!(left_correction < 0.0);
+		assert!((left_correction + right_correction).abs() < 1e-9);
+	}
+
+	#[test]
+	fn follows_corner_closely() {
+		let waypoints = (0..=40)
+			.map(|i| Vec2::new(i as f64, 0.0))
+			.chain((1..=40).map(|i| Vec2::new(40.0, i as f64)))
+			.collect();
+		let constraints = PursuitConstraints::new(30.0, 60.0, 20.0, 0.0, 0.0);
+		let mut stanley = Stanley::new(waypoints, 2.0, 1.0, 1.0, constraints);
+		let (mut position, mut heading) = (Vec2::new(0.0, 0.5), 0.0);
+
+		for _ in 0..2000 {
+			if stanley.is_finished(position) {
+				break;
+			}
+
+			let (linear_velocity, correction) = stanley.update(position, heading, DT);
+
+			// Turn towards the corrected heading like a proportional turn controller would.
+			position += Vec2::from_polar(linear_velocity * DT.as_secs_f64(), heading);
+			heading += 20.0 * correction * DT.as_secs_f64();
+
+			let first_leg = position.distance(Vec2::new(position.x.min(40.0), 0.0));
+			let second_leg = position.distance(Vec2::new(40.0, position.y.max(0.0)));
+			assert!(first_leg.min(second_leg) < 2.0);
+		}
+
+		assert!(stanley.is_finished(position));
+		assert!(stanley.progress().distance_remaining < 1.5);
+	}
+
+	#[test]
+	fn finishes_sparse_paths() {
+		let paths = [
+			vec![Vec2::new(0.0, 0.0), Vec2::new(48.0, 0.0)],
+			vec![Vec2::new(0.0, 0.0), Vec2::new(24.0, 0.0), Vec2::new(48.0, 24.0)],
+		];
+
+		for waypoints in paths {
+			let constraints = PursuitConstraints::new(30.0, 60.0, 20.0, 0.0, 0.0);
+			let mut stanley = Stanley::new(waypoints, 2.0, 1.0, 1.0, constraints);
+			let (mut position, mut heading) = (Vec2::new(0.0, 0.0), 0.0);
+
+			for _ in 0..2000 {
+				if stanley.is_finished(position) {
+					break;
+				}
+
+				let (linear_velocity, correction) = stanley.update(position, heading, DT);
+
+				position += Vec2::from_polar(linear_velocity * DT.as_secs_f64(), heading);
+				heading += 20.0 * correction * DT.as_secs_f64();
+			}
+
+			// The robot must not stall partway along the final segment.
+			assert!(stanley.is_finished(position));
+		}
+	}
+}
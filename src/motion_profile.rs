@@ -0,0 +1,531 @@
+use core::time::Duration;
+use num_traits::real::Real;
+
+/// The desired state of a system at a point in time along a [`MotionProfile`].
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct MotionState {
+    /// The distance travelled from the start of the profile.
+    pub position: f64,
+
+    /// The rate of change of position.
+    pub velocity: f64,
+
+    /// The rate of change of velocity.
+    pub acceleration: f64,
+}
+
+/// A time-parameterized plan for moving a system from rest to rest over a distance.
+///
+/// Rather than immediately setting a controller's setpoint to its final target, a motion profile
+/// describes how the setpoint should move over time such that the system never exceeds its
+/// velocity and acceleration limits. The profile is sampled at the time elapsed since the motion
+/// began to get the current setpoint.
+pub trait MotionProfile: Send + Sync + 'static {
+    /// The total time it takes to complete the profile.
+    fn duration(&self) -> Duration;
+
+    /// The total distance travelled by the profile. This will be negative for backwards motions.
+    fn distance(&self) -> f64;
+
+    /// Get the desired state at a given time since the start of the profile.
+    ///
+    /// Times past the end of the profile will return the final state.
+    fn sample(&self, time: Duration) -> MotionState;
+}
+
+/// A motion profile with a trapezoidal velocity curve.
+///
+/// The profile accelerates at a constant rate up to its maximum velocity, cruises at that velocity,
+/// then decelerates to a stop at the end of the distance. If the distance is too short to reach the
+/// maximum velocity, the cruise phase is skipped and the velocity curve becomes a triangle.
+///
+/// If the maximum velocity or acceleration is zero or NaN, or the acceleration is infinite, the profile
+/// can't be timed and instead finishes instantly at the end of the distance.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct TrapezoidalProfile {
+    distance: f64,
+    max_velocity: f64,
+    max_acceleration: f64,
+    acceleration_time: f64,
+    cruise_time: f64,
+    peak_velocity: f64,
+}
+
+impl TrapezoidalProfile {
+    /// Construct a new [`TrapezoidalProfile`] that travels a distance with velocity and acceleration limits.
+    pub fn new(distance: f64, max_velocity: f64, max_acceleration: f64) -> Self {
+        let (max_velocity, max_acceleration) = (max_velocity.abs(), max_acceleration.abs());
+        let magnitude = distance.abs();
+
+        if !(max_velocity > 0.0 && max_acceleration > 0.0)
+            || !max_acceleration.is_finite()
+            || !magnitude.is_finite()
+        {
+            return Self {
+                distance,
+                max_velocity,
+                max_acceleration,
+                ..Default::default()
+            };
+        }
+
+        let mut acceleration_time = max_velocity / max_acceleration;
+        let mut peak_velocity = max_velocity;
+
+        // If accelerating and decelerating at full velocity would overshoot the distance,
+        // the maximum velocity is never reached.
+        if max_acceleration * acceleration_time.powi(2) > magnitude {
+            acceleration_time = (magnitude / max_acceleration).sqrt();
+            peak_velocity = max_acceleration * acceleration_time;
+        }
+
+        let acceleration_distance = max_acceleration * acceleration_time.powi(2);
+        let cruise_time = if peak_velocity > 0.0 {
+            (magnitude - acceleration_distance) / peak_velocity
+        } else {
+            0.0
+        };
+
+        Self {
+            distance,
+            max_velocity,
+            max_acceleration,
+            acceleration_time,
+            cruise_time,
+            peak_velocity,
+        }
+    }
+
+    /// Get the maximum velocity of the profile.
+    pub fn max_velocity(&self) -> f64 {
+        self.max_velocity
+    }
+
+    /// Get the maximum acceleration of the profile.
+    pub fn max_acceleration(&self) -> f64 {
+        self.max_acceleration
+    }
+
+    /// Get the highest velocity reached by the profile, which will be less than the maximum
+    /// velocity for short distances.
+    pub fn peak_velocity(&self) -> f64 {
+        self.peak_velocity
+    }
+}
+
+impl MotionProfile for TrapezoidalProfile {
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(2.0 * self.acceleration_time + self.cruise_time)
+    }
+
+    fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    fn sample(&self, time: Duration) -> MotionState {
+        let t = time.as_secs_f64();
+        let (a, v) = (self.max_acceleration, self.peak_velocity);
+        let deceleration_start = self.acceleration_time + self.cruise_time;
+        let end = deceleration_start + self.acceleration_time;
+        let acceleration_distance = 0.5 * a * self.acceleration_time.powi(2);
+
+        let (position, velocity, acceleration) = if t < self.acceleration_time {
+            (0.5 * a * t.powi(2), a * t, a)
+        } else if t < deceleration_start {
+            let t = t - self.acceleration_time;
+
+            (acceleration_distance + v * t, v, 0.0)
+        } else if t < end {
+            let t = t - deceleration_start;
+
+            (
+                acceleration_distance + v * self.cruise_time + v * t - 0.5 * a * t.powi(2),
+                v - a * t,
+                -a,
+            )
+        } else {
+            (self.distance.abs(), 0.0, 0.0)
+        };
+
+        let sign = self.distance.signum();
+
+        MotionState {
+            position: position * sign,
+            velocity: velocity * sign,
+            acceleration: acceleration * sign,
+        }
+    }
+}
+
+/// A jerk-limited motion profile with an S-shaped velocity curve.
+///
+/// Unlike a [`TrapezoidalProfile`], acceleration is ramped up and down at a limited rate (jerk) rather than
+/// changing instantly, producing smoother motion with less wheel slip. The profile consists of seven
+/// phases of constant jerk: ramping acceleration up, holding it, ramping it down, cruising, then the
+/// same three phases mirrored to decelerate.
+///
+/// If the distance is too short to reach the maximum velocity, the peak velocity is lowered. If the
+/// peak velocity is too low to reach the maximum acceleration, the constant acceleration phases are
+/// skipped.
+///
+/// If the maximum velocity, acceleration, or jerk is zero or NaN, or the acceleration or jerk is infinite,
+/// the profile can't be timed and instead finishes instantly at the end of the distance.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct SCurveProfile {
+    distance: f64,
+    max_velocity: f64,
+    max_acceleration: f64,
+    max_jerk: f64,
+    peak_velocity: f64,
+
+    /// The duration and jerk of each phase.
+    phases: [(f64, f64); 7],
+
+    /// The state at the start of each phase.
+    boundaries: [MotionState; 7],
+}
+
+impl SCurveProfile {
+    /// Construct a new [`SCurveProfile`] that travels a distance with velocity, acceleration, and jerk limits.
+    pub fn new(distance: f64, max_velocity: f64, max_acceleration: f64, max_jerk: f64) -> Self {
+        let (v, a, j) = (max_velocity.abs(), max_acceleration.abs(), max_jerk.abs());
+        let magnitude = distance.abs();
+
+        if !(v > 0.0 && a > 0.0 && j > 0.0)
+            || !a.is_finite()
+            || !j.is_finite()
+            || !magnitude.is_finite()
+        {
+            return Self {
+                distance,
+                max_velocity: v,
+                max_acceleration: a,
+                max_jerk: j,
+                ..Default::default()
+            };
+        }
+
+        // Find the peak velocity, which is lower than the maximum velocity when the distance is too short
+        // to reach it. Accelerating from rest to a velocity `v` with a symmetric S-curve covers a distance
+        // of `v * t / 2`, where `t` is the time spent accelerating.
+        let acceleration_time = |v: f64| {
+            if v * j < a.powi(2) {
+                2.0 * (v / j).sqrt()
+            } else {
+                v / a + a / j
+            }
+        };
+
+        let mut peak_velocity = v;
+        if peak_velocity * acceleration_time(peak_velocity) > magnitude {
+            // Solve `v * (v / a + a / j) = d` for when the maximum acceleration is still reached.
+            let reaching_acceleration =
+                (-(a.powi(2) / j) + ((a.powi(2) / j).powi(2) + 4.0 * a * magnitude).sqrt()) / 2.0;
+
+            peak_velocity = if reaching_acceleration * j >= a.powi(2) {
+                reaching_acceleration
+            } else {
+                // Otherwise, solve `v * 2 * sqrt(v / j) = d`.
+                (magnitude.powi(2) * j / 4.0).cbrt()
+            };
+        }
+
+        let (jerk_time, constant_acceleration_time) = if peak_velocity * j < a.powi(2) {
+            ((peak_velocity / j).sqrt(), 0.0)
+        } else {
+            (a / j, peak_velocity / a - a / j)
+        };
+        let cruise_time = if peak_velocity > 0.0 {
+            ((magnitude - peak_velocity * acceleration_time(peak_velocity)) / peak_velocity).max(0.0)
+        } else {
+            0.0
+        };
+
+        let phases = [
+            (jerk_time, j),
+            (constant_acceleration_time, 0.0),
+            (jerk_time, -j),
+            (cruise_time, 0.0),
+            (jerk_time, -j),
+            (constant_acceleration_time, 0.0),
+            (jerk_time, j),
+        ];
+
+        let mut boundaries = [MotionState::default(); 7];
+        for i in 1..boundaries.len() {
+            let (duration, jerk) = phases[i - 1];
+            boundaries[i] = integrate(boundaries[i - 1], jerk, duration);
+        }
+
+        Self {
+            distance,
+            max_velocity: v,
+            max_acceleration: a,
+            max_jerk: j,
+            peak_velocity,
+            phases,
+            boundaries,
+        }
+    }
+
+    /// Get the maximum velocity of the profile.
+    pub fn max_velocity(&self) -> f64 {
+        self.max_velocity
+    }
+
+    /// Get the maximum acceleration of the profile.
+    pub fn max_acceleration(&self) -> f64 {
+        self.max_acceleration
+    }
+
+    /// Get the maximum jerk of the profile.
+    pub fn max_jerk(&self) -> f64 {
+        self.max_jerk
+    }
+
+    /// Get the highest velocity reached by the profile, which will be less than the maximum
+    /// velocity for short distances.
+    pub fn peak_velocity(&self) -> f64 {
+        self.peak_velocity
+    }
+}
+
+/// Integrate a motion state forward in time with a constant jerk.
+fn integrate(state: MotionState, jerk: f64, time: f64) -> MotionState {
+    MotionState {
+        position: state.position
+            + state.velocity * time
+            + state.acceleration * time.powi(2) / 2.0
+            + jerk * time.powi(3) / 6.0,
+        velocity: state.velocity + state.acceleration * time + jerk * time.powi(2) / 2.0,
+        acceleration: state.acceleration + jerk * time,
+    }
+}
+
+impl MotionProfile for SCurveProfile {
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.phases.iter().map(|(duration, _)| duration).sum())
+    }
+
+    fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    fn sample(&self, time: Duration) -> MotionState {
+        let mut t = time.as_secs_f64();
+        let sign = self.distance.signum();
+
+        for (i, (duration, jerk)) in self.phases.iter().enumerate() {
+            if t < *duration {
+                let state = integrate(self.boundaries[i], *jerk, t);
+
+                return MotionState {
+                    position: state.position * sign,
+                    velocity: state.velocity * sign,
+                    acceleration: state.acceleration * sign,
+                };
+            }
+
+            t -= duration;
+        }
+
+        MotionState {
+            position: self.distance,
+            velocity: 0.0,
+            acceleration: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(time: f64) -> Duration {
+        Duration::from_secs_f64(time)
+    }
+
+    /// Samples a profile finely and checks that its position and velocity never jump and its limits
+    /// are respected, including across phase boundaries.
+    fn assert_continuous(profile: &impl MotionProfile, max_velocity: f64, max_acceleration: f64) {
+        let step = 1e-4;
+        let end = profile.duration().as_secs_f64() + 0.01;
+        let mut previous = profile.sample(Duration::ZERO);
+        let mut t = step;
+
+        assert_eq!(previous.position, 0.0);
+
+        while t < end {
+            let state = profile.sample(seconds(t));
+
+            assert!(state.velocity.abs() <= max_velocity + 1e-6);
+            assert!(state.acceleration.abs() <= max_acceleration + 1e-6);
+            assert!((state.position - previous.position).abs() <= max_velocity * step + 1e-6);
+            assert!((state.velocity - previous.velocity).abs() <= max_acceleration * step + 1e-6);
+
+            previous = state;
+            t += step;
+        }
+
+        assert!((previous.position - profile.distance()).abs() < 1e-9);
+        assert_eq!(previous.velocity, 0.0);
+    }
+
+    #[test]
+    fn trapezoidal_reaches_max_velocity() {
+        let profile = TrapezoidalProfile::new(10.0, 2.0, 1.0);
+
+        assert!((profile.duration().as_secs_f64() - 7.0).abs() < 1e-9);
+        assert_eq!(profile.peak_velocity(), 2.0);
+        assert_eq!(profile.sample(seconds(3.5)).velocity, 2.0);
+        assert!((profile.sample(seconds(3.5)).position - 5.0).abs() < 1e-9);
+        assert_continuous(&profile, 2.0, 1.0);
+    }
+
+    #[test]
+    fn trapezoidal_short_distance_is_triangular() {
+        let profile = TrapezoidalProfile::new(1.0, 2.0, 1.0);
+
+        assert!((profile.duration().as_secs_f64() - 2.0).abs() < 1e-9);
+        assert!((profile.peak_velocity() - 1.0).abs() < 1e-9);
+
+        let midpoint = profile.sample(seconds(1.0));
+        assert!((midpoint.position - 0.5).abs() < 1e-9);
+        assert!((midpoint.velocity - 1.0).abs() < 1e-9);
+        assert_continuous(&profile, 1.0, 1.0);
+    }
+
+    #[test]
+    fn trapezoidal_negative_distance_is_mirrored() {
+        let forwards = TrapezoidalProfile::new(10.0, 2.0, 1.0);
+        let backwards = TrapezoidalProfile::new(-10.0, 2.0, 1.0);
+
+        assert_eq!(forwards.duration(), backwards.duration());
+        for t in [0.5, 2.0, 3.5, 6.5, 8.0] {
+            let (forwards, backwards) = (forwards.sample(seconds(t)), backwards.sample(seconds(t)));
+
+            assert_eq!(forwards.position, -backwards.position);
+            assert_eq!(forwards.velocity, -backwards.velocity);
+            assert_eq!(forwards.acceleration, -backwards.acceleration);
+        }
+        assert_continuous(&backwards, 2.0, 1.0);
+    }
+
+    #[test]
+    fn trapezoidal_zero_distance_finishes_instantly() {
+        let profile = TrapezoidalProfile::new(0.0, 2.0, 1.0);
+
+        assert_eq!(profile.duration(), Duration::ZERO);
+        assert_eq!(profile.sample(Duration::ZERO), MotionState::default());
+    }
+
+    #[test]
+    fn trapezoidal_phase_boundaries_are_continuous() {
+        let profile = TrapezoidalProfile::new(10.0, 2.0, 1.0);
+
+        // Acceleration ends at 2 seconds and deceleration starts at 5 seconds.
+        for boundary in [2.0, 5.0] {
+            let before = profile.sample(seconds(boundary - 1e-9));
+            let after = profile.sample(seconds(boundary));
+
+            assert!((before.position - after.position).abs() < 1e-6);
+            assert!((before.velocity - after.velocity).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn trapezoidal_invalid_limits_finish_instantly() {
+        for (max_velocity, max_acceleration) in [
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (0.0, 1.0),
+            (f64::NAN, 1.0),
+            (2.0, f64::INFINITY),
+        ] {
+            let profile = TrapezoidalProfile::new(5.0, max_velocity, max_acceleration);
+
+            assert_eq!(profile.duration(), Duration::ZERO);
+            assert_eq!(profile.sample(Duration::ZERO).position, 5.0);
+        }
+
+        let unlimited = TrapezoidalProfile::new(1.0, f64::INFINITY, 1.0);
+        assert!((unlimited.duration().as_secs_f64() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn s_curve_reaches_all_limits() {
+        let profile = SCurveProfile::new(100.0, 10.0, 5.0, 10.0);
+
+        // Jerk phases take 0.5 seconds, constant acceleration phases 1.5 seconds, and cruising
+        // takes 7.5 seconds.
+        assert!((profile.duration().as_secs_f64() - 12.5).abs() < 1e-9);
+        assert_eq!(profile.peak_velocity(), 10.0);
+        assert!((profile.sample(seconds(1.0)).acceleration - 5.0).abs() < 1e-9);
+        assert!((profile.sample(seconds(6.25)).velocity - 10.0).abs() < 1e-9);
+        assert_continuous(&profile, 10.0, 5.0);
+    }
+
+    #[test]
+    fn s_curve_short_distance_reduces_peak_velocity() {
+        let profile = SCurveProfile::new(10.0, 10.0, 5.0, 10.0);
+        let half = profile.duration().as_secs_f64() / 2.0;
+
+        // The peak velocity is lowered, but is still high enough to reach the maximum acceleration.
+        assert!(profile.peak_velocity() < 10.0);
+        assert!(profile.peak_velocity() * 10.0 >= 5.0f64.powi(2));
+        assert!((profile.sample(seconds(half)).velocity - profile.peak_velocity()).abs() < 1e-6);
+        assert!((profile.sample(seconds(half)).position - 5.0).abs() < 1e-6);
+        assert!((profile.sample(seconds(0.6)).acceleration - 5.0).abs() < 1e-9);
+        assert_continuous(&profile, profile.peak_velocity(), 5.0);
+    }
+
+    #[test]
+    fn s_curve_skips_constant_acceleration() {
+        let profile = SCurveProfile::new(1.0, 10.0, 5.0, 10.0);
+        let half = profile.duration().as_secs_f64() / 2.0;
+        let jerk_time = half / 2.0;
+
+        // The peak velocity is too low to reach the maximum acceleration, so acceleration is ramped
+        // straight back down once it peaks.
+        assert!(profile.peak_velocity() * 10.0 < 5.0f64.powi(2));
+        assert!((profile.sample(seconds(jerk_time)).acceleration - 10.0 * jerk_time).abs() < 1e-6);
+        assert!(profile.sample(seconds(jerk_time)).acceleration < 5.0);
+        assert!((profile.sample(seconds(half)).velocity - profile.peak_velocity()).abs() < 1e-6);
+        assert!((profile.sample(seconds(half)).position - 0.5).abs() < 1e-6);
+        assert_continuous(&profile, profile.peak_velocity(), 5.0);
+    }
+
+    #[test]
+    fn s_curve_negative_and_zero_distance() {
+        let forwards = SCurveProfile::new(10.0, 10.0, 5.0, 10.0);
+        let backwards = SCurveProfile::new(-10.0, 10.0, 5.0, 10.0);
+
+        assert_eq!(forwards.duration(), backwards.duration());
+        for t in [0.2, 1.0, 2.0, 3.0] {
+            let (forwards, backwards) = (forwards.sample(seconds(t)), backwards.sample(seconds(t)));
+
+            assert_eq!(forwards.position, -backwards.position);
+        }
+        assert_continuous(&backwards, 10.0, 5.0);
+
+        let zero = SCurveProfile::new(0.0, 10.0, 5.0, 10.0);
+        assert_eq!(zero.duration(), Duration::ZERO);
+        assert_eq!(zero.sample(Duration::ZERO), MotionState::default());
+    }
+
+    #[test]
+    fn s_curve_invalid_limits_finish_instantly() {
+        for (max_velocity, max_acceleration, max_jerk) in [
+            (0.0, 0.0, 0.0),
+            (10.0, 5.0, 0.0),
+            (10.0, 0.0, 10.0),
+            (10.0, 5.0, f64::NAN),
+            (10.0, f64::INFINITY, 10.0),
+        ] {
+            let profile = SCurveProfile::new(5.0, max_velocity, max_acceleration, max_jerk);
+
+            assert_eq!(profile.duration(), Duration::ZERO);
+            assert_eq!(profile.sample(Duration::ZERO).position, 5.0);
+        }
+    }
+}
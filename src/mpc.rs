@@ -0,0 +1,459 @@
+use core::time::Duration;
+use num_traits::real::Real;
+
+use crate::{
+    chassis::ChassisModel,
+    math::{normalize_angle, Vec2},
+    trajectory::TrajectoryPoint,
+};
+
+/// A linear time-varying model predictive controller (MPC) for tracking a trajectory with a differential drive.
+///
+/// Each update, the controller linearizes the drivetrain's kinematics around the next `N` points of a reference
+/// trajectory, then searches for the sequence of left and right wheel velocities over that horizon that best
+/// balances tracking error (weighted by `state_weights`) against deviation from the trajectory's own wheel
+/// velocities (weighted by `input_weights`). Only the first step of that sequence is applied, and the rest is
+/// kept as a starting guess for the next update.
+///
+/// Unlike RAMSETE or PID, MPC respects the drivetrain's limits while planning rather than saturating afterwards.
+/// Wheel velocities are limited to what `max_power` can sustain according to the chassis model's feedforward,
+/// and their rate of change is limited to `max_acceleration`.
+///
+/// All working memory is stored in fixed-size arrays, so updates do not allocate. Solve time grows linearly with
+/// both `N` and `iterations`: each update evaluates the cost and its gradient `iterations + 1` times, at roughly
+/// 50 multiply-adds per step of the horizon. With the default of 20 iterations and a 20 step horizon, this is
+/// about 20,000 multiply-adds per update. If updates overrun the drivetrain's 10 millisecond loop, lower
+/// `iterations` or `N`.
+///
+/// The output is a linear and angular velocity for the chassis, which can be converted into motor power with
+/// [`DifferentialDrivetrain::control_velocity`](crate::drivetrain::DifferentialDrivetrain::control_velocity).
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub struct ModelPredictiveController<const N: usize> {
+    /// The chassis model used to predict motion and compute wheel velocity limits.
+    pub model: ChassisModel,
+
+    /// The cost weights of x, y, and heading error.
+    pub state_weights: [f64; 3],
+
+    /// The cost weights of the left and right wheel velocities' deviation from the reference.
+    pub input_weights: [f64; 2],
+
+    /// The maximum motor power that can be applied to a side of the drivetrain, as a percentage of the
+    /// motors' full 12 volts (the same units as the chassis model's feedforward output).
+    pub max_power: f64,
+
+    /// The maximum rate of change of a wheel's velocity.
+    pub max_acceleration: f64,
+
+    /// The number of optimization iterations performed per update.
+    pub iterations: usize,
+
+    /// The time between each step of the prediction horizon.
+    pub timestep: Duration,
+
+    solution: [[f64; 2]; N],
+    previous_input: [f64; 2],
+    step_size: f64,
+}
+
+impl<const N: usize> ModelPredictiveController<N> {
+    /// Construct a new [`ModelPredictiveController`] with a horizon of `N` steps, each `timestep` apart.
+    pub fn new(
+        model: ChassisModel,
+        state_weights: [f64; 3],
+        input_weights: [f64; 2],
+        max_power: f64,
+        max_acceleration: f64,
+        timestep: Duration,
+    ) -> Self {
+        Self {
+            model,
+            state_weights,
+            input_weights,
+            max_power,
+            max_acceleration,
+            iterations: 20,
+            timestep,
+            solution: [[0.0; 2]; N],
+            previous_input: [0.0; 2],
+            step_size: 1.0,
+        }
+    }
+
+    /// Clears the stored solution and previously applied wheel velocities, such as when starting a new trajectory.
+    pub fn reset(&mut self) {
+        self.solution = [[0.0; 2]; N];
+        self.previous_input = [0.0; 2];
+        self.step_size = 1.0;
+    }
+
+    /// Get the maximum wheel velocity that `max_power` can sustain.
+    pub fn max_wheel_velocity(&self) -> f64 {
+        let feedforward = self.model.feedforward;
+
+        if feedforward.kv > 0.0 {
+            ((self.max_power - feedforward.ks) / feedforward.kv).max(0.0)
+        } else {
+            f64::MAX
+        }
+    }
+
+    /// Compute a chassis velocity as a tuple (`linear_velocity`, `angular_velocity`).
+    ///
+    /// - `pose` is the robot's current (`position`, `heading`), such as from [`Tracking`](crate::tracking::Tracking).
+    /// - `reference` is the next `N` points of the trajectory, spaced `timestep` apart, starting at the point the
+    /// robot should be at now.
+    pub fn update(&mut self, pose: (Vec2, f64), reference: &[TrajectoryPoint; N]) -> (f64, f64) {
+        if N == 0 {
+            return (0.0, 0.0);
+        }
+
+        let (position, heading) = pose;
+        let initial_error = [
+            position.x - reference[0].position.x,
+            position.y - reference[0].position.y,
+            normalize_angle(heading - reference[0].heading),
+        ];
+
+        // Linearize the kinematics around each reference point.
+        let mut dynamics = [([[0.0; 3]; 3], [[0.0; 2]; 3]); N];
+        let mut reference_inputs = [[0.0; 2]; N];
+        for (k, point) in reference.iter().enumerate() {
+            dynamics[k] = self.linearize(point);
+
+            let (left, right) = self
+                .model
+                .wheel_velocities(point.linear_velocity, point.angular_velocity);
+            reference_inputs[k] = [left, right];
+        }
+
+        // Projected gradient descent with a backtracking line search, warm started from the previous solution.
+        let mut inputs = self.solution;
+        self.project(&mut inputs);
+        let (mut cost, mut gradient) = self.evaluate(&inputs, &initial_error, &dynamics, &reference_inputs);
+        let mut step_size = (self.step_size * 2.0).min(1.0);
+
+        for _ in 0..self.iterations {
+            let mut candidate = inputs;
+            for k in 0..N {
+                for i in 0..2 {
+                    candidate[k][i] -= step_size * gradient[k][i];
+                }
+            }
+            self.project(&mut candidate);
+
+            // Sufficient decrease condition for projected gradient steps.
+            let mut predicted = cost;
+            for k in 0..N {
+                for i in 0..2 {
+                    let difference = candidate[k][i] - inputs[k][i];
+                    predicted += gradient[k][i] * difference + difference.powi(2) / (2.0 * step_size);
+                }
+            }
+
+            let (candidate_cost, candidate_gradient) =
+                self.evaluate(&candidate, &initial_error, &dynamics, &reference_inputs);
+
+            if candidate_cost <= predicted {
+                inputs = candidate;
+                cost = candidate_cost;
+                gradient = candidate_gradient;
+            } else {
+                step_size /= 2.0;
+            }
+        }
+
+        self.step_size = step_size;
+
+        // Apply the first input, then shift the solution forward a step to warm start the next update.
+        let [left, right] = inputs[0];
+        self.previous_input = inputs[0];
+        for k in 0..N {
+            self.solution[k] = inputs[(k + 1).min(N - 1)];
+        }
+
+        (
+            (left + right) / 2.0,
+            (right - left) / self.model.track_width,
+        )
+    }
+
+    /// Compute the discrete error dynamics matrices `A` and `B` around a reference point.
+    fn linearize(&self, point: &TrajectoryPoint) -> ([[f64; 3]; 3], [[f64; 2]; 3]) {
+        let dt = self.timestep.as_secs_f64();
+        let (sin, cos) = point.heading.sin_cos();
+        let velocity = point.linear_velocity;
+        let width = self.model.track_width;
+
+        (
+            [
+                [1.0, 0.0, -velocity * sin * dt],
+                [0.0, 1.0, velocity * cos * dt],
+                [0.0, 0.0, 1.0],
+            ],
+            [
+                [cos * dt / 2.0, cos * dt / 2.0],
+                [sin * dt / 2.0, sin * dt / 2.0],
+                [-dt / width, dt / width],
+            ],
+        )
+    }
+
+    /// Make a sequence of wheel velocities feasible by limiting each step's velocity and change in velocity.
+    fn project(&self, inputs: &mut [[f64; 2]; N]) {
+        let max_velocity = self.max_wheel_velocity();
+        let max_change = self.max_acceleration * self.timestep.as_secs_f64();
+        let mut previous = self.previous_input;
+
+        for input in inputs.iter_mut() {
+            for i in 0..2 {
+                let min = (-max_velocity).max(previous[i] - max_change);
+                let max = max_velocity.min(previous[i] + max_change);
+
+                input[i] = input[i].max(min).min(max);
+            }
+
+            previous = *input;
+        }
+    }
+
+    /// Compute the cost of a sequence of wheel velocities and its gradient.
+    fn evaluate(
+        &self,
+        inputs: &[[f64; 2]; N],
+        initial_error: &[f64; 3],
+        dynamics: &[([[f64; 3]; 3], [[f64; 2]; 3]); N],
+        reference_inputs: &[[f64; 2]; N],
+    ) -> (f64, [[f64; 2]; N]) {
+        let (q, r) = (self.state_weights, self.input_weights);
+
+        // Simulate the error forward over the horizon.
+        let mut errors = [[0.0; 3]; N];
+        let mut deviations = [[0.0; 2]; N];
+        let mut cost = 0.0;
+        let mut error = *initial_error;
+
+        for k in 0..N {
+            let (a, b) = &dynamics[k];
+            deviations[k] = [
+                inputs[k][0] - reference_inputs[k][0],
+                inputs[k][1] - reference_inputs[k][1],
+            ];
+
+            let mut next = [0.0; 3];
+            for i in 0..3 {
+                next[i] = a[i][0] * error[0]
+                    + a[i][1] * error[1]
+                    + a[i][2] * error[2]
+                    + b[i][0] * deviations[k][0]
+                    + b[i][1] * deviations[k][1];
+                cost += q[i] * next[i].powi(2);
+            }
+            for i in 0..2 {
+                cost += r[i] * deviations[k][i].powi(2);
+            }
+
+            errors[k] = next;
+            error = next;
+        }
+
+        // Propagate the cost's sensitivity to each error backwards to find the gradient.
+        let mut gradient = [[0.0; 2]; N];
+        let mut costate = [0.0; 3];
+
+        for k in (0..N).rev() {
+            let (a, b) = &dynamics[k];
+
+            for i in 0..3 {
+                costate[i] += 2.0 * q[i] * errors[k][i];
+            }
+            for i in 0..2 {
+                gradient[k][i] = 2.0 * r[i] * deviations[k][i]
+                    + b[0][i] * costate[0]
+                    + b[1][i] * costate[1]
+                    + b[2][i] * costate[2];
+            }
+
+            let mut previous_costate = [0.0; 3];
+            for i in 0..3 {
+                previous_costate[i] =
+                    a[0][i] * costate[0] + a[1][i] * costate[1] + a[2][i] * costate[2];
+            }
+            costate = previous_costate;
+        }
+
+        (cost, gradient)
+    }
+}
+
+/// A simple simulation of a differential drive, for testing controllers away from a robot.
+///
+/// Each side of the drivetrain is modeled as a wheel whose acceleration follows the chassis model's
+/// feedforward, so applying the feedforward's predicted power for a velocity will reach that velocity.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct DifferentialDriveSimulation {
+    /// The model describing the simulated chassis.
+    pub model: ChassisModel,
+
+    /// The simulated position.
+    pub position: Vec2,
+
+    /// The simulated heading in radians, increasing counterclockwise.
+    pub heading: f64,
+
+    /// The simulated left and right wheel velocities.
+    pub wheel_velocities: (f64, f64),
+}
+
+impl DifferentialDriveSimulation {
+    /// Construct a new [`DifferentialDriveSimulation`] at rest at a pose.
+    pub fn new(model: ChassisModel, position: Vec2, heading: f64) -> Self {
+        Self {
+            model,
+            position,
+            heading,
+            wheel_velocities: (0.0, 0.0),
+        }
+    }
+
+    /// Advance the simulation by a timestep with left and right motor power applied.
+    pub fn step(&mut self, powers: (f64, f64), dt: Duration) {
+        let dt = dt.as_secs_f64();
+        let feedforward = self.model.feedforward;
+
+        let step_wheel = |velocity: f64, power: f64| {
+            // The velocity the power would hold at steady state.
+            let steady_velocity = if feedforward.kv > 0.0 {
+                if power.abs() <= feedforward.ks {
+                    0.0
+                } else {
+                    (power - power.signum() * feedforward.ks) / feedforward.kv
+                }
+            } else {
+                velocity
+            };
+
+            if feedforward.ka > 0.0 && feedforward.kv > 0.0 {
+                // First-order response towards the steady state velocity with time constant ka / kv.
+                let time_constant = feedforward.ka / feedforward.kv;
+                steady_velocity + (velocity - steady_velocity) * (-dt / time_constant).exp()
+            } else {
+                steady_velocity
+            }
+        };
+
+        let left = step_wheel(self.wheel_velocities.0, powers.0);
+        let right = step_wheel(self.wheel_velocities.1, powers.1);
+        self.wheel_velocities = (left, right);
+
+        let linear_velocity = (left + right) / 2.0;
+        let angular_velocity = (right - left) / self.model.track_width;
+
+        self.position += Vec2::from_polar(
+            linear_velocity * dt,
+            self.heading + angular_velocity * dt / 2.0,
+        );
+        self.heading += angular_velocity * dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::SimpleMotorFeedforward;
+    use core::f64::consts::FRAC_PI_2;
+
+    const DT: Duration = Duration::from_millis(10);
+    const TIMESTEP: f64 = 0.05;
+
+    fn model() -> ChassisModel {
+        ChassisModel::new(12.0, SimpleMotorFeedforward::new(2.0, 1.5, 0.2))
+    }
+
+    fn controller(max_power: f64) -> ModelPredictiveController<20> {
+        ModelPredictiveController::new(
+            model(),
+            [1.0, 1.0, 50.0],
+            [0.001, 0.001],
+            max_power,
+            200.0,
+            Duration::from_secs_f64(TIMESTEP),
+        )
+    }
+
+    /// A counterclockwise circle with a radius of 30 driven at 20 units per second, starting at the
+    /// origin facing along the x axis.
+    fn circle(time: f64) -> TrajectoryPoint {
+        let (radius, velocity) = (30.0, 20.0);
+        let angular_velocity = velocity / radius;
+        let angle = angular_velocity * time - FRAC_PI_2;
+
+        TrajectoryPoint::new(
+            Vec2::new(radius * angle.cos(), radius + radius * angle.sin()),
+            angular_velocity * time,
+            velocity,
+            angular_velocity,
+        )
+    }
+
+    /// Runs a controller against a simulation starting off the circle, returning the largest tracking
+    /// error over the second half of the run.
+    fn track(controller: &mut ModelPredictiveController<20>) -> f64 {
+        let mut simulation = DifferentialDriveSimulation::new(model(), Vec2::new(0.0, 4.0), 0.3);
+        let mut max_error: f64 = 0.0;
+
+        for i in 0..600 {
+            let time = i as f64 * DT.as_secs_f64();
+            let reference = core::array::from_fn(|k| circle(time + k as f64 * TIMESTEP));
+
+            let (linear_velocity, angular_velocity) =
+                controller.update((simulation.position, simulation.heading), &reference);
+
+            simulation.step(model().wheel_powers(linear_velocity, angular_velocity), DT);
+
+            if i >= 300 {
+                let error = simulation.position.distance(circle(time + DT.as_secs_f64()).position);
+                max_error = max_error.max(error);
+            }
+        }
+
+        max_error
+    }
+
+    #[test]
+    fn tracks_circle_from_offset_start() {
+        // The default iteration count, whose cost is documented, must be enough to track accurately.
+        let mut controller = controller(100.0);
+        assert_eq!(controller.iterations, 20);
+
+        assert!(track(&mut controller) < 0.05);
+    }
+
+    #[test]
+    fn respects_wheel_velocity_limit() {
+        // 20 power can only sustain a wheel velocity of 12, which is slower than the circle.
+        let mut controller = controller(20.0);
+        assert_eq!(controller.max_wheel_velocity(), 12.0);
+
+        let reference = core::array::from_fn(|k| circle(k as f64 * TIMESTEP));
+        for _ in 0..50 {
+            let (linear_velocity, angular_velocity) =
+                controller.update((Vec2::new(0.0, 0.0), 0.0), &reference);
+            let (left, right) = model().wheel_velocities(linear_velocity, angular_velocity);
+
+            assert!(left.abs() <= 12.0 + 1e-9 && right.abs() <= 12.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn simulation_reaches_feedforward_velocity() {
+        let mut simulation = DifferentialDriveSimulation::new(model(), Vec2::new(0.0, 0.0), 0.0);
+
+        for _ in 0..500 {
+            simulation.step(model().wheel_powers(10.0, 0.0), DT);
+        }
+
+        assert!((simulation.wheel_velocities.0 - 10.0).abs() < 1e-6);
+        assert!(simulation.position.y.abs() < 1e-9);
+    }
+}
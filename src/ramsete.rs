@@ -0,0 +1,150 @@
+use num_traits::real::Real;
+
+use crate::math::{normalize_angle, Vec2};
+
+/// A RAMSETE nonlinear trajectory tracking controller.
+///
+/// RAMSETE follows a time-parameterized trajectory by comparing the robot's current pose (position and heading)
+/// to the pose it should be at on the trajectory, then correcting the trajectory's velocities to converge back
+/// onto it. Unlike driving to a point with PID, it accounts for the nonholonomic constraints of a differential
+/// drive, meaning it can correct for sideways error that the robot cannot drive in directly.
+///
+/// The output is a linear and angular velocity for the chassis, which can be converted into motor power with
+/// [`DifferentialDrivetrain::control_velocity`](crate::drivetrain::DifferentialDrivetrain::control_velocity).
+///
+/// # Tuning
+///
+/// - `b` (> 0) acts like a proportional gain. Larger values make convergence more aggressive.
+/// - `zeta` (between 0 and 1) acts like a damping term. Larger values add more damping.
+///
+/// Values of `b = 2.0` and `zeta = 0.7` are a common starting point for robots measured in meters. Since `b`
+/// scales with the inverse square of distance, it should be scaled down accordingly for other units
+/// (e.g. `b ≈ 0.0013` when measuring in inches).
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct RamseteController {
+    /// The aggressiveness constant.
+    pub b: f64,
+
+    /// The damping constant.
+    pub zeta: f64,
+}
+
+impl RamseteController {
+    /// Construct a new [`RamseteController`] from its tuning constants.
+    pub fn new(b: f64, zeta: f64) -> Self {
+        Self { b, zeta }
+    }
+
+    /// Compute a chassis velocity as a tuple (`linear_velocity`, `angular_velocity`).
+    ///
+    /// - `pose` is the robot's current (`position`, `heading`), such as from [`Tracking`](crate::tracking::Tracking).
+    /// - `target_pose` is the (`position`, `heading`) the robot should be at on the trajectory.
+    /// - `target_velocity` is the (`linear_velocity`, `angular_velocity`) the robot should be moving at
+    /// on the trajectory.
+    ///
+    /// Headings and angular velocities are in radians, increasing counterclockwise.
+    pub fn update(
+        &self,
+        pose: (Vec2, f64),
+        target_pose: (Vec2, f64),
+        target_velocity: (f64, f64),
+    ) -> (f64, f64) {
+        let (position, heading) = pose;
+        let (target_position, target_heading) = target_pose;
+        let (linear_velocity, angular_velocity) = target_velocity;
+
+        // Error in the robot's local frame, where x is forwards and y is to the left.
+        let error = (target_position - position).rotate(-heading);
+        let heading_error = normalize_angle(target_heading - heading);
+
+        let k = 2.0
+            * self.zeta
+            * (angular_velocity.powi(2) + self.b * linear_velocity.powi(2)).sqrt();
+
+        (
+            linear_velocity * heading_error.cos() + k * error.x,
+            angular_velocity
+                + k * heading_error
+                + self.b * linear_velocity * sinc(heading_error) * error.y,
+        )
+    }
+}
+
+/// The unnormalized sinc function, `sin(x) / x`, which approaches 1 as `x` approaches 0.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        x.sin() / x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Integrates a unicycle model following a reference trajectory with RAMSETE, returning the final
+    /// distance from the reference.
+    fn track(
+        controller: RamseteController,
+        start: (Vec2, f64),
+        reference: impl Fn(f64) -> ((Vec2, f64), (f64, f64)),
+    ) -> f64 {
+        let dt = 0.01;
+        let (mut position, mut heading) = start;
+
+        for i in 0..1000 {
+            let (target_pose, target_velocity) = reference(i as f64 * dt);
+            let (linear_velocity, angular_velocity) =
+                controller.update((position, heading), target_pose, target_velocity);
+
+            position += Vec2::from_polar(linear_velocity * dt, heading);
+            heading += angular_velocity * dt;
+        }
+
+        let ((target_position, _), _) = reference(1000.0 * dt);
+        position.distance(target_position)
+    }
+
+    #[test]
+    fn zero_error_follows_reference_velocity() {
+        let controller = RamseteController::new(2.0, 0.7);
+        let pose = (Vec2::new(1.0, 2.0), 0.5);
+
+        assert_eq!(controller.update(pose, pose, (1.0, 0.25)), (1.0, 0.25));
+    }
+
+    #[test]
+    fn turns_towards_lateral_error() {
+        let controller = RamseteController::new(2.0, 0.7);
+        let (_, angular_velocity) = controller.update(
+            (Vec2::new(0.0, 0.0), 0.0),
+            (Vec2::new(0.0, 0.5), 0.0),
+            (1.0, 0.0),
+        );
+
+        // The reference is to the left, so the robot should turn counterclockwise.
+        assert!(angular_velocity > 0.0);
+    }
+
+    #[test]
+    fn converges_onto_line() {
+        let controller = RamseteController::new(2.0, 0.7);
+        let error = track(controller, (Vec2::new(0.0, 0.5), 0.3), |t| {
+            ((Vec2::new(t, 0.0), 0.0), (1.0, 0.0))
+        });
+
+        assert!(error < 0.05);
+    }
+
+    #[test]
+    fn converges_onto_circle() {
+        let controller = RamseteController::new(2.0, 0.7);
+        let error = track(controller, (Vec2::new(0.2, -0.2), 0.0), |t| {
+            // A circle of radius 1 centered at (0, 1), starting at the origin.
+            ((Vec2::new(t.sin(), 1.0 - t.cos()), t), (1.0, 1.0))
+        });
+
+        assert!(error < 0.05);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::math::Vec2;
+
+/// The desired state of a robot at a point in time along a trajectory.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct TrajectoryPoint {
+    /// The desired position.
+    pub position: Vec2,
+
+    /// The desired heading in radians, increasing counterclockwise.
+    pub heading: f64,
+
+    /// The desired linear velocity.
+    pub linear_velocity: f64,
+
+    /// The desired angular velocity in radians, increasing counterclockwise.
+    pub angular_velocity: f64,
+}
+
+impl TrajectoryPoint {
+    /// Construct a new [`TrajectoryPoint`] from a pose and velocities.
+    pub fn new(position: Vec2, heading: f64, linear_velocity: f64, angular_velocity: f64) -> Self {
+        Self {
+            position,
+            heading,
+            linear_velocity,
+            angular_velocity,
+        }
+    }
+}